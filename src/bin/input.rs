@@ -1,47 +1,72 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use std::{error::Error, io, process::Command, fs::File, collections::HashMap};
+use std::{error::Error, io::{self, Write}, process::Command, collections::HashMap, fs, path::{Path, PathBuf}, sync::mpsc::{self, Receiver, TryRecvError}, thread, time::{Duration, Instant}};
 use core::fmt::{Debug};
+use unicode_width::UnicodeWidthStr;
 
 use tui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
     text::{Span, Spans, Text},
-    widgets::{Block, Borders, List, ListItem, Paragraph, ListState},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, ListState, Wrap},
     Frame, Terminal,
 };
-use unicode_width::UnicodeWidthStr;
 
-enum InputMode {
-    Type,
-    Select,
-    Confirm,
-}
+use git_message::{
+    bump::{self, Version},
+    changelog,
+    config::{Config, LayoutConfig, NarrowLayout},
+    display,
+    i18n::{self, Catalog},
+    keymap::{Action, KeyChord, Keymap},
+    draft::Draft,
+    history::{History, SelectDefault},
+    hook,
+    lint::{self, Linter},
+    log::{self, LogConfig, LogEntry, LogScope},
+    scope::{self, Suggestion},
+    source::CommandSource,
+    template::{Template, MISSION_ID, SCOPES, SUBJECT},
+    theme::Theme,
+    tracker::{self, Ticket, TrackerConfig},
+};
+
+// 界面模式与快捷键表共用
+use git_message::keymap::Mode as InputMode;
 
 #[derive(Debug)]
 struct StatefulList<'a, T:Debug> {
     state: ListState,
     items: & 'a Vec<T>,
+    /// Indexes of the items left by the filter, `state` points into this
+    visible: Vec<usize>,
+    /// First visible entry; tui keeps its own offset private, clicks need it
+    offset: usize,
 }
 
 impl<'a, T:Debug> StatefulList<'a, T> {
     // 初始化
-    fn with_items(items:& Vec<T>) -> StatefulList<T> {
+    fn with_items(items:& Vec<T>) -> StatefulList<'_, T> {
         StatefulList {
             state: ListState::default(),
             items,
+            visible: (0..items.len()).collect(),
+            offset: 0,
         }
     }
 
     // 向下选择
     fn next(&mut self) {
+        if self.visible.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
-                if i >= self.items.len() - 1 {
+                if i >= self.visible.len() - 1 {
                     0
                 } else {
                     i + 1
@@ -54,10 +79,13 @@ impl<'a, T:Debug> StatefulList<'a, T> {
 
     // 向上选择
     fn previous(&mut self) {
+        if self.visible.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.items.len() - 1
+                    self.visible.len() - 1
                 } else {
                     i - 1
                 }
@@ -71,6 +99,68 @@ impl<'a, T:Debug> StatefulList<'a, T> {
     fn unselect(&mut self) {
         self.state.select(None);
     }
+
+    // 滚轮移动选择, 到两端时停住
+    fn scroll(&mut self, down: bool) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let i = match (self.state.selected(), down) {
+            (Some(i), true) => (i + 1).min(self.visible.len() - 1),
+            (Some(i), false) => i.saturating_sub(1),
+            (None, _) => self.offset.min(self.visible.len() - 1),
+        };
+        self.state.select(Some(i));
+    }
+
+    // 保证选中的元素在 height 行内完整可见, 元素的行数由 item_height 给出
+    fn scroll_into_view(&mut self, height: usize, item_height: impl Fn(&T) -> usize) {
+        let heights: Vec<usize> = self.visible.iter().map(|&i| item_height(&self.items[i])).collect();
+        self.offset = self.offset.min(heights.len().saturating_sub(1));
+        if let Some(selected) = self.state.selected().filter(|&i| i < heights.len()) {
+            if selected < self.offset {
+                self.offset = selected;
+            }
+            while self.offset < selected && heights[self.offset..=selected].iter().sum::<usize>() > height {
+                self.offset += 1;
+            }
+        }
+        // 列表变短时不在底部留空
+        while self.offset > 0 && heights[self.offset - 1..].iter().sum::<usize>() <= height {
+            self.offset -= 1;
+        }
+    }
+
+    // 列表第 row 行显示的元素
+    fn item_at(&self, row: usize, item_height: impl Fn(&T) -> usize) -> Option<usize> {
+        let mut bottom = 0;
+        for (position, &i) in self.visible.iter().enumerate().skip(self.offset) {
+            bottom += item_height(&self.items[i]);
+            if row < bottom {
+                return Some(position);
+            }
+        }
+        None
+    }
+
+    // 当前选中的元素
+    fn selected(&self) -> Option<&'a T> {
+        let items = self.items;
+        self.state.selected().and_then(|i| self.visible.get(i)).map(|&i| &items[i])
+    }
+
+    // 选中items中的第index个元素
+    fn select(&mut self, index: Option<usize>) {
+        self.state.select(index.and_then(|index| self.visible.iter().position(|&i| i == index)));
+    }
+
+    // 只保留满足条件的元素, 选中的元素被筛掉时选中第一个
+    fn filter(&mut self, keep: impl Fn(&T) -> bool) {
+        let selected = self.state.selected().and_then(|i| self.visible.get(i).copied());
+        self.visible = (0..self.items.len()).filter(|&i| keep(&self.items[i])).collect();
+        let position = selected.and_then(|selected| self.visible.iter().position(|&i| i == selected));
+        self.state.select(position.or_else(|| (!self.visible.is_empty()).then_some(0)));
+    }
 }
 
 
@@ -84,56 +174,912 @@ struct App<'a> {
     messages: Vec<String>,
     state_ful_list: StatefulList<'a, (String,String)>,
     command_map: Vec<(String, String)>,
-    select_map: & 'a HashMap<String, Vec<(String, String)>>
+    select_map: & 'a HashMap<String, Vec<(String, String)>>,
+    /// Long descriptions of the choices, keyed by step type and value
+    descriptions: & 'a HashMap<String, HashMap<String, String>>,
+    /// Values highlighted in the log panel headers
+    known_values: Vec<&'a str>,
+    log_config: LogConfig,
+    log: Vec<LogEntry>,
+    /// Whether the branch has an upstream to limit the `branch` log scope to
+    upstream: bool,
+    template: Template,
+    /// Answers pre-filled into each step, e.g. parsed from HEAD with `--amend`
+    prefill: Vec<String>,
+    /// Body and trailers of the pre-filled message that no step covers, kept after the header
+    unparsed: String,
+    /// Answers given on the command line, these steps are not asked
+    fixed: Vec<Option<String>>,
+    amend: bool,
+    /// Extra options for `git commit`: config `signoff`/`gpgSign` plus everything after `--`
+    commit_args: Vec<String>,
+    /// `Signed-off-by` trailer shown in the Confirm preview when signing off
+    signoff: Option<String>,
+    output: Output,
+    /// Draft offered at startup
+    draft: Option<Draft>,
+    history: History,
+    select_default: SelectDefault,
+    /// Position while cycling through the history of the current step with Up/Down
+    history_index: Option<usize>,
+    /// What was typed before cycling through the history
+    history_input: String,
+    /// Scopes suggested by `scopeRules` for the staged files
+    suggestions: Vec<Suggestion>,
+    tracker: Option<TrackerConfig>,
+    /// Ticket of the entered MissionId, shown in the right pane
+    ticket: Option<Ticket>,
+    /// Lookup of the entered MissionId running in the background
+    lookup: Option<Receiver<Ticket>>,
+    /// Warning or error shown under the prompt
+    notice: Option<String>,
+    /// UI strings in the configured language
+    text: &'static Catalog,
+    theme: Theme,
+    keymap: Keymap,
+    /// The key binding overlay is open
+    show_help: bool,
+    /// Text typed to narrow the select list, `None` when not filtering
+    filter: Option<String>,
+    /// Log entries scrolled past in the right pane
+    log_offset: usize,
+    /// Capture the mouse, `mouse` in the config
+    mouse: bool,
+    /// Where the select list and the log were last drawn, for mouse events
+    list_area: Rect,
+    log_area: Rect,
+    /// List position and time of the last click, to detect a double click
+    last_click: Option<(usize, Instant)>,
+    layout: LayoutConfig,
+    /// The right pane is shown, toggled by a key
+    show_preview: bool,
+}
+
+/// Two clicks on the same entry within this time submit it
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Where the finished message goes
+enum Output {
+    /// Run `git commit`
+    Commit,
+    /// Write into the message file git passed to the prepare-commit-msg hook
+    MessageFile(PathBuf),
+    /// `--print` / `--dry-run`: write the result to stdout
+    Print(Format),
+    /// `--output <file>`: write the result to a file, e.g. for `git commit -F`
+    File(PathBuf, Format),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Format {
+    /// The rendered message only
+    #[default]
+    Text,
+    /// Every step value plus the rendered message
+    Json,
+}
+
+/// How the user left `run_app`
+enum Outcome {
+    Submitted,
+    Aborted,
 }
 
 impl <'a> App <'a> {
-    fn new(items: & 'a Vec<(String, String)>, command_map: Vec<(String, String)>, select_map: & 'a HashMap<String, Vec<(String, String)>>) -> App<'a> {
+    fn new(items: & 'a Vec<(String, String)>, config: & 'a Config) -> App<'a> {
         App {
             input: String::new(),
             input_mode: InputMode::Type,
             messages: Vec::new(),
             state_ful_list: StatefulList::with_items(items),
-            command_map,
-            select_map,
+            command_map: config.command_map.clone(),
+            select_map: &config.select_map,
+            descriptions: &config.descriptions,
+            known_values: config.known_values(),
+            log_config: config.log,
+            log: log::read_log(&config.log),
+            upstream: log::has_upstream(),
+            template: config.template(),
+            prefill: vec![],
+            unparsed: String::new(),
+            fixed: vec![],
+            amend: false,
+            commit_args: config.commit_args(),
+            signoff: None,
+            output: Output::Commit,
+            draft: None,
+            history: History::load(),
+            select_default: config.select_default,
+            history_index: None,
+            history_input: String::new(),
+            suggestions: vec![],
+            tracker: config.tracker.clone(),
+            ticket: None,
+            lookup: None,
+            notice: None,
+            text: config.locale.catalog(),
+            theme: config.theme,
+            keymap: config.keys.clone(),
+            show_help: false,
+            filter: None,
+            log_offset: 0,
+            mouse: config.mouse,
+            list_area: Rect::default(),
+            log_area: Rect::default(),
+            last_click: None,
+            layout: config.layout,
+            show_preview: true,
         }
     }
-    fn set_mode(& mut self, mode: InputMode, key: & String) {
-        if key == "" {
+    fn set_mode(& mut self, mode: InputMode, key: &str) {
+        if key.is_empty() {
             self.input_mode = mode;
             return;
         }
-        match mode {
-            InputMode::Select => self.state_ful_list = StatefulList::with_items(self.select_map.get(key).unwrap()),
-            _ => {}
+        if let InputMode::Select = mode {
+            self.state_ful_list = StatefulList::with_items(self.select_map.get(key).unwrap());
+            self.filter = None;
         }
         self.input_mode = mode;
     }
 
-    fn commit(& mut self) {
-        let command = self.messages.join(" ");
-        Command::new("git")
-            .args(["commit", "-m", &command])
-            .output()
-            .expect("failed to execute process");
+    // 进入下一个步骤, 并填入预设的答案
+    fn next_step(& mut self) {
+        // 跳过命令行中已给出答案的步骤
+        while let Some(Some(answer)) = self.fixed.get(self.messages.len()) {
+            self.messages.push(answer.clone());
+        }
+        if self.messages.len() >= self.command_map.len() {
+            self.set_mode(InputMode::Confirm, "");
+            return;
+        }
+        let step = self.messages.len();
+        let key = self.command_map[step].1.clone();
+        let prefill = self.prefill.get(step).cloned().unwrap_or_default();
+        self.history_index = None;
+        if self.select_map.contains_key(&key) {
+            self.set_mode(InputMode::Select, &key);
+            // 没有预设答案时默认选中建议的scope或最近/最常用的选项
+            let suggested = self.suggestions.first().filter(|_| key == SCOPES).map(|s| s.scope.as_str());
+            let default = if prefill.is_empty() {
+                suggested.or_else(|| self.history.default_choice(&key, self.select_default)).unwrap_or_default()
+            } else {
+                &prefill
+            };
+            let index = self.state_ful_list.items.iter().position(|(_, value)| value == default);
+            self.state_ful_list.select(index);
+        } else {
+            self.set_mode(InputMode::Type, &key);
+            // 没有预设答案时用任务标题作为默认概述
+            self.input = match &self.ticket {
+                Some(Ticket::Found { title, .. }) if prefill.is_empty() && key == SUBJECT => title.clone(),
+                _ => prefill,
+            };
+        }
+    }
+
+    // 提交当前输入; 任务ID先在后台查询, 不存在时再次提交才使用
+    fn submit_input(& mut self) {
+        if self.lookup.is_some() {
+            return;
+        }
+        let key = &self.command_map[self.messages.len()].1;
+        let id = self.input.trim().to_string();
+        let confirmed = matches!(&self.ticket, Some(Ticket::NotFound { id: not_found }) if *not_found == id);
+        if let (true, Some(tracker)) = (key == MISSION_ID && !id.is_empty() && !confirmed, &self.tracker) {
+            let (sender, receiver) = mpsc::channel();
+            let tracker = tracker.clone();
+            self.notice = Some(i18n::fill(self.text.mission_checking, &[("id", &id)]));
+            thread::spawn(move || sender.send(tracker::lookup(&tracker, &id)));
+            self.lookup = Some(receiver);
+            return;
+        }
+        self.notice = None;
+        self.messages.push(self.input.drain(..).collect());
+        self.next_step();
+    }
+
+    // 后台查询结束后继续提交; 任务不存在时留在本步骤
+    fn poll_lookup(& mut self) {
+        let ticket = match self.lookup.as_ref().map(Receiver::try_recv) {
+            Some(Ok(ticket)) => ticket,
+            Some(Err(TryRecvError::Empty)) | None => return,
+            Some(Err(TryRecvError::Disconnected)) => {
+                let id = self.input.trim().to_string();
+                Ticket::Unavailable { id, reason: "lookup failed".to_string() }
+            }
+        };
+        self.lookup = None;
+        let (Ticket::Found { id, .. } | Ticket::NotFound { id } | Ticket::Unavailable { id, .. }) = &ticket;
+        // 查询期间输入已改变时丢弃结果
+        if id != self.input.trim() {
+            self.notice = None;
+            return;
+        }
+        match &ticket {
+            Ticket::NotFound { id } => {
+                self.notice = Some(i18n::fill(self.text.mission_not_found, &[("id", id)]));
+                self.ticket = Some(ticket);
+                return;
+            }
+            // 离线时只提示, 不阻止提交
+            Ticket::Unavailable { id, reason } => {
+                self.notice = Some(i18n::fill(self.text.mission_not_checked, &[("id", id), ("reason", reason)]));
+            }
+            Ticket::Found { .. } => self.notice = None,
+        }
+        self.ticket = Some(ticket);
+        self.messages.push(self.input.drain(..).collect());
+        self.next_step();
+    }
+
+    // 修改输入时放弃正在进行的查询
+    fn edit_input(& mut self) {
+        if self.lookup.take().is_some() {
+            self.notice = None;
+        }
+        self.history_index = None;
+    }
+
+    // 恢复草稿中的答案, 全部答完时直接进入确认界面
+    fn restore_draft(& mut self) {
+        if let Some(draft) = self.draft.take() {
+            self.messages = draft.answers_for(self.template.steps());
+        }
+        self.next_step();
+    }
+
+    fn discard_draft(& mut self) {
+        self.draft = None;
+        Draft::clear();
+        self.next_step();
+    }
+
+    // 保存已完成的答案, 下次启动时可恢复
+    fn save_draft(&self) {
+        if !self.messages.is_empty() {
+            if let Err(err) = Draft::new(self.template.steps(), &self.messages).save() {
+                eprintln!("rcz: could not save the draft: {}", err);
+            }
+        }
+    }
+
+    // 完成失败时保存草稿, 成功时记录历史
+    fn finish_or_save(& mut self) -> io::Result<()> {
+        if let Err(err) = self.finish() {
+            self.save_draft();
+            return Err(err);
+        }
+        Draft::clear();
+        for ((_, key), value) in self.command_map.iter().zip(&self.messages) {
+            self.history.record(key, value);
+        }
+        if let Err(err) = self.history.save() {
+            eprintln!("rcz: could not save the history: {}", err);
+        }
+        Ok(())
+    }
+
+    // 当前步骤的历史记录, 最近使用的在前
+    fn step_history(&self) -> Vec<String> {
+        let key = &self.command_map[self.messages.len()].1;
+        self.history.recent(key).into_iter().map(str::to_string).collect()
+    }
+
+    // Up/Down 在历史记录中切换, older 为 true 时向更早的记录移动
+    fn cycle_history(& mut self, older: bool) {
+        let values = self.step_history();
+        let index = match (self.history_index, older) {
+            (None, true) if !values.is_empty() => Some(0),
+            (Some(i), true) if i + 1 < values.len() => Some(i + 1),
+            (Some(0), false) => None,
+            (Some(i), false) => Some(i - 1),
+            (index, _) => index,
+        };
+        if self.history_index.is_none() && index.is_some() {
+            self.history_input = self.input.clone();
+        }
+        self.input = match index {
+            Some(i) => values[i].clone(),
+            None => self.history_input.clone(),
+        };
+        self.history_index = index;
+    }
+
+    // Tab 用历史记录补全当前输入
+    fn complete_from_history(& mut self) {
+        let key = &self.command_map[self.messages.len()].1;
+        if let Some(value) = self.history.complete(key, &self.input) {
+            self.input = value.to_string();
+        }
+    }
+
+    // 切换git log展示范围
+    fn toggle_log_scope(& mut self) {
+        self.log_config.scope = self.log_config.scope.toggle();
+        self.log = log::read_log(&self.log_config);
+        self.log_offset = 0;
+    }
+
+    // 向下滚动git log, 到底后回到开头
+    fn scroll_log(& mut self) {
+        self.log_offset = (self.log_offset + 1) % self.log.len().max(1);
+    }
+
+    // 提交选中的元素
+    fn submit_selection(& mut self) {
+        // 命令失败时的错误项不能选择; 列表中只有错误项时以空答案跳过本步骤
+        let selectable = self.state_ful_list.items.iter().any(|choice| !CommandSource::is_error(choice));
+        let value = match self.state_ful_list.selected() {
+            Some(choice) if !CommandSource::is_error(choice) => choice.1.clone(),
+            _ if !selectable => String::new(),
+            _ => return,
+        };
+        self.messages.push(value);
+        self.next_step();
+    }
+
+    // 点击选中列表项, 双击提交; 滚轮滚动列表或git log
+    fn handle_mouse(& mut self, event: MouseEvent) {
+        let in_list = self.input_mode == InputMode::Select && contains(self.list_area, event.column, event.row);
+        let in_log = contains(self.log_area, event.column, event.row);
+        match event.kind {
+            MouseEventKind::ScrollDown if in_list => self.state_ful_list.scroll(true),
+            MouseEventKind::ScrollUp if in_list => self.state_ful_list.scroll(false),
+            MouseEventKind::ScrollDown if in_log => self.log_offset = (self.log_offset + 1).min(self.log.len().saturating_sub(1)),
+            MouseEventKind::ScrollUp if in_log => self.log_offset = self.log_offset.saturating_sub(1),
+            MouseEventKind::Down(MouseButton::Left) if in_list => {
+                // 去掉上下边框
+                let row = event.row - self.list_area.y;
+                if row == 0 || row + 1 >= self.list_area.height {
+                    return;
+                }
+                if let Some(index) = self.state_ful_list.item_at(row as usize - 1, item_height) {
+                    let double = matches!(self.last_click, Some((last, at)) if last == index && at.elapsed() < DOUBLE_CLICK);
+                    self.state_ful_list.state.select(Some(index));
+                    self.last_click = if double { None } else { Some((index, Instant::now())) };
+                    if double {
+                        self.submit_selection();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 打开或关闭选择列表的筛选
+    fn toggle_filter(& mut self) {
+        self.filter = match self.filter {
+            Some(_) => None,
+            None => Some(String::new()),
+        };
+        self.apply_filter();
+    }
+
+    // 按名称或值筛选, 不区分大小写
+    fn apply_filter(& mut self) {
+        let filter = self.filter.as_deref().unwrap_or_default().to_lowercase();
+        self.state_ful_list.filter(|(name, value)| name.to_lowercase().contains(&filter) || value.to_lowercase().contains(&filter));
+    }
+
+    fn finish(& mut self) -> io::Result<()> {
+        match &self.output {
+            Output::Commit => self.commit()?,
+            Output::MessageFile(path) => fs::write(path, self.message() + "\n")?,
+            Output::Print(format) => println!("{}", self.result(*format)),
+            Output::File(path, format) => fs::write(path, self.result(*format) + "\n")?,
+        }
+        Ok(())
+    }
+
+    // 渲染的提交信息, 后面保留预设信息中没有对应步骤的正文
+    fn message(&self) -> String {
+        let message = self.template.render(&self.messages);
+        if self.unparsed.is_empty() {
+            message
+        } else {
+            format!("{}\n\n{}", message, self.unparsed)
+        }
+    }
+
+    fn result(&self, format: Format) -> String {
+        match format {
+            Format::Text => self.message(),
+            Format::Json => self.result_json().to_string(),
+        }
+    }
+
+    // 各步骤的值以及最终的提交信息
+    fn result_json(&self) -> serde_json::Value {
+        let steps: serde_json::Map<String, serde_json::Value> = self
+            .command_map
+            .iter()
+            .zip(&self.messages)
+            .map(|((_, key), value)| (key.clone(), serde_json::Value::from(value.as_str())))
+            .collect();
+        serde_json::json!({
+            "steps": steps,
+            "message": self.message(),
+        })
+    }
+
+    fn commit(& mut self) -> io::Result<()> {
+        let command = self.message();
+        let mut args = vec!["commit"];
+        if self.amend {
+            args.push("--amend");
+        }
+        args.extend(self.commit_args.iter().map(String::as_str));
+        args.extend(["-m", &command]);
+        let output = Command::new("git").args(&args).output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let reason = if stderr.trim().is_empty() { stdout } else { stderr };
+            return Err(io::Error::other(format!("git commit failed: {}", reason.trim())));
+        }
+        Ok(())
     }
 }
 
-const COMMAND_KEY: &str = "messages";
+/// Command line options
+#[derive(Debug, Default)]
+struct Options {
+    subcommand: Subcommand,
+    /// `--amend`: edit the message of HEAD and run `git commit --amend`
+    amend: bool,
+    /// `(flag, value)` answers such as `-t feat`, resolved against the steps once the config is loaded
+    answers: Vec<(String, String)>,
+    /// `--answers <file|->`: JSON object of answers keyed by step type
+    answers_file: Option<String>,
+    /// `--print` / `--dry-run`: output the result instead of committing
+    print: bool,
+    /// `--output <file>`: write the result to a file instead of committing
+    output: Option<PathBuf>,
+    /// `--format <text|json>` of `--print` / `--output`
+    format: Option<Format>,
+    /// Everything after `--`, passed on to `git commit`
+    git_args: Vec<String>,
+    /// `--retry`: restore the draft of the last aborted or failed commit
+    retry: bool,
+    /// `--no-color`: draw without colours, like `NO_COLOR`
+    no_color: bool,
+}
 
-fn main() -> Result<(), Box<dyn Error>> {
-    // setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+#[derive(Debug, Default)]
+enum Subcommand {
+    /// Ask for every step and commit
+    #[default]
+    Interactive,
+    /// `rcz hook install`
+    HookInstall,
+    /// `rcz hook run <file> [source] [sha]`, invoked by git as prepare-commit-msg
+    HookRun { file: PathBuf, source: Option<String> },
+    /// `rcz lint <file>` or `rcz lint [--from <rev>] [--to <rev>]`
+    Lint { file: Option<PathBuf>, from: Option<String>, to: Option<String> },
+    /// `rcz changelog [--from <tag>] [--to <rev>]`
+    Changelog { from: Option<String>, to: Option<String> },
+    /// `rcz bump [--dry-run]`
+    Bump { dry_run: bool },
+}
 
+impl Options {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.peekable();
+        if args.peek().map(String::as_str) == Some("hook") {
+            args.next();
+            options.subcommand = match args.next().as_deref() {
+                Some("install") => Subcommand::HookInstall,
+                Some("run") => {
+                    let file = args.next().ok_or("hook run: missing commit message file")?;
+                    let source = args.next();
+                    // 第三个参数为commit sha, 不需要
+                    args.next();
+                    Subcommand::HookRun { file: PathBuf::from(file), source }
+                }
+                _ => return Err("usage: rcz hook <install|run>".to_string()),
+            };
+        } else if args.peek().map(String::as_str) == Some("bump") {
+            args.next();
+            let mut dry_run = false;
+            for arg in args.by_ref() {
+                match arg.as_str() {
+                    "--dry-run" => dry_run = true,
+                    _ => return Err(format!("unknown argument: {}", arg)),
+                }
+            }
+            options.subcommand = Subcommand::Bump { dry_run };
+        } else if args.peek().map(String::as_str) == Some("changelog") {
+            args.next();
+            let (mut from, mut to) = (None, None);
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--from" => from = Some(args.next().ok_or("--from: missing revision")?),
+                    "--to" => to = Some(args.next().ok_or("--to: missing revision")?),
+                    _ => return Err(format!("unknown argument: {}", arg)),
+                }
+            }
+            options.subcommand = Subcommand::Changelog { from, to };
+        } else if args.peek().map(String::as_str) == Some("lint") {
+            args.next();
+            let (mut file, mut from, mut to) = (None, None, None);
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--from" => from = Some(args.next().ok_or("--from: missing revision")?),
+                    "--to" => to = Some(args.next().ok_or("--to: missing revision")?),
+                    _ if file.is_none() && !arg.starts_with('-') => file = Some(PathBuf::from(arg)),
+                    _ => return Err(format!("unknown argument: {}", arg)),
+                }
+            }
+            if file.is_some() && (from.is_some() || to.is_some()) {
+                return Err("lint: pass either a message file or --from/--to".to_string());
+            }
+            options.subcommand = Subcommand::Lint { file, from, to };
+        }
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--" => options.git_args = args.by_ref().collect(),
+                "--amend" => options.amend = true,
+                "--retry" => options.retry = true,
+                "--no-color" => options.no_color = true,
+                "--print" | "--dry-run" => options.print = true,
+                "--output" => options.output = Some(PathBuf::from(args.next().ok_or("--output: missing file")?)),
+                "--answers" => options.answers_file = Some(args.next().ok_or("--answers: missing file")?),
+                "--format" => {
+                    options.format = match args.next().as_deref() {
+                        Some("text") => Some(Format::Text),
+                        Some("json") => Some(Format::Json),
+                        _ => return Err("--format: expected `text` or `json`".to_string()),
+                    }
+                }
+                _ if arg.starts_with('-') => {
+                    let (flag, value) = match arg.split_once('=') {
+                        Some((flag, value)) => (flag.to_string(), value.to_string()),
+                        None => {
+                            let value = args.next().ok_or(format!("{}: missing value", arg))?;
+                            (arg, value)
+                        }
+                    };
+                    options.answers.push((flag, value));
+                }
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+        if options.format.is_some() && !options.print && options.output.is_none() {
+            return Err("--format: only with --print or --output".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("rcz: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let options = Options::parse(std::env::args().skip(1))?;
+    let mut output = Output::Commit;
+    let mut prefill_message = None;
+    match options.subcommand {
+        Subcommand::Interactive => {}
+        Subcommand::Lint { file, from, to } => {
+            let clean = run_lint(&Config::load()?, file, from, to)?;
+            std::process::exit(if clean { 0 } else { 1 });
+        }
+        Subcommand::Changelog { from, to } => return write_changelog(&Config::load()?, from, to),
+        Subcommand::Bump { dry_run } => return run_bump(&Config::load()?, dry_run),
+        Subcommand::HookInstall => {
+            let path = hook::install(&std::env::current_exe()?)?;
+            println!("installed {}", path.display());
+            return Ok(());
+        }
+        Subcommand::HookRun { file, source } => {
+            if hook::should_skip(source.as_deref()) {
+                return Ok(());
+            }
+            // `git commit --amend` / `-c` 时文件中已有原提交信息, 去掉git添加的注释
+            if source.as_deref() == Some("commit") {
+                prefill_message = Some(lint::strip_comments(&fs::read_to_string(&file)?));
+            }
+            output = Output::MessageFile(file);
+        }
+    }
+    let mut config = match Config::load() {
+        Ok(config) => config,
+        // hook模式下配置有误时不阻止提交
+        Err(err) if matches!(output, Output::MessageFile(_)) => {
+            eprintln!("rcz: {}; leaving the commit message as is", err);
+            return Ok(());
+        }
+        Err(err) => return Err(err),
+    };
+    if options.amend {
+        prefill_message = Some(log::head_message().ok_or("--amend: HEAD has no commit message to edit")?);
+    }
+    let answers_json = match options.answers_file.as_deref() {
+        Some("-") => Some(serde_json::from_reader(io::stdin())?),
+        Some(file) => Some(serde_json::from_reader(fs::File::open(file)?)?),
+        None => None,
+    };
+    let fixed = resolve_answers(&config, &options.answers, answers_json.as_ref())?;
+    // 只为需要显示的步骤运行命令
+    let shown: Vec<String> = config.command_map.iter().zip(&fixed).filter(|(_, answer)| answer.is_none()).map(|((_, key), _)| key.clone()).collect();
+    config.load_sources(&shown);
+    // 按暂存的文件把建议的scope排到最前面
+    let suggestions = scope::suggest(&config.scope_rules, &scope::staged_paths());
+    if let Some(choices) = config.select_map.get_mut(SCOPES) {
+        scope::reorder(choices, &suggestions);
+    }
+    if let Some(path) = options.output {
+        output = Output::File(path, options.format.unwrap_or_default());
+    } else if options.print {
+        output = Output::Print(options.format.unwrap_or_default());
+    }
 
     // create app and run it
     let items = vec![("feat:     A new feature".to_string(), "feat".to_string()),("feat:     A new feature".to_string(), "feat".to_string()), ("feat:     A new feature".to_string(), "feat".to_string())];
-    let (command_map, select_map) = read_json_file()?;
-    let app = App::new(&items, command_map, &select_map);
+    let mut app = App::new(&items, &config);
+    app.suggestions = suggestions;
+    if let Some(message) = prefill_message {
+        app.prefill = app.template.parse(&message, &config.select_map);
+        app.unparsed = app.template.unparsed(&message);
+    }
+    app.amend = options.amend;
+    app.commit_args.extend(options.git_args);
+    // 只有自己运行 git commit 时才会加上签名尾注
+    if matches!(output, Output::Commit) && app.commit_args.iter().any(|arg| arg == "-s" || arg == "--signoff") {
+        app.signoff = log::signoff_trailer();
+    }
+    let hook_mode = matches!(output, Output::MessageFile(_));
+    app.output = output;
+    app.fixed = fixed;
+    // https://no-color.org: 设置了非空的NO_COLOR时不使用颜色
+    if options.no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        app.theme = app.theme.without_color();
+    }
+
+    // 所有步骤都已通过参数给出, 不需要打开界面
+    if app.fixed.iter().all(Option::is_some) {
+        app.next_step();
+        app.finish_or_save()?;
+        return Ok(());
+    }
+
+    let draft = Draft::load();
+    if options.retry {
+        app.draft = Some(draft.ok_or("--retry: there is no draft to restore")?);
+        app.restore_draft();
+    } else if draft.is_some() {
+        app.draft = draft;
+        app.input_mode = InputMode::Restore;
+    } else {
+        app.next_step();
+    }
+
+    // hook模式下 stdin/stdout 由git接管, 直接使用终端
+    let outcome = if hook_mode && cfg!(unix) {
+        run_tui(fs::OpenOptions::new().write(true).open("/dev/tty")?, &mut app)?
+    } else {
+        run_tui(io::stdout(), &mut app)?
+    };
+    match outcome {
+        Outcome::Submitted => app.finish_or_save()?,
+        Outcome::Aborted => {
+            app.save_draft();
+            if hook_mode {
+                eprintln!("rcz: commit message aborted");
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Map the `--answers` JSON object and the `(flag, value)` pairs to one optional
+/// answer per step, validated like `rcz lint`; flags win over the JSON answers
+fn resolve_answers(config: &Config, flags: &[(String, String)], json: Option<&serde_json::Value>) -> Result<Vec<Option<String>>, Box<dyn Error>> {
+    // (来源, 步骤, 值)
+    let mut answers: Vec<(String, &str, String)> = vec![];
+    if let Some(json) = json {
+        let object = json.as_object().ok_or("--answers: expected a JSON object keyed by step type")?;
+        for (key, value) in object {
+            let step = config.command_map.iter().map(|(_, step)| step.as_str()).find(|step| step == key);
+            let step = step.ok_or(format!("--answers: unknown step `{}`", key))?;
+            let value = value.as_str().ok_or(format!("--answers: `{}` must be a string", step))?;
+            answers.push(("--answers".to_string(), step, value.to_string()));
+        }
+    }
+    for (flag, value) in flags {
+        let step = config.step_for_flag(flag).ok_or(format!("unknown argument: {}", flag))?;
+        answers.push((flag.clone(), step, value.clone()));
+    }
+
+    let linter = Linter::new(config)?;
+    let mut fixed = vec![None; config.command_map.len()];
+    let mut errors = vec![];
+    for (source, step, value) in answers {
+        let index = config.command_map.iter().position(|(_, key)| key == step).unwrap();
+        if let Some(violation) = linter.check(step, &value).filter(|_| !value.is_empty()) {
+            errors.push(format!("{} ({}): {}", source, step, violation.message));
+        }
+        fixed[index] = Some(value);
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("\n").into());
+    }
+    Ok(fixed)
+}
+
+/// Print the violations of every linted message, returns whether all of them passed
+fn run_lint(config: &Config, file: Option<PathBuf>, from: Option<String>, to: Option<String>) -> Result<bool, Box<dyn Error>> {
+    let linter = Linter::new(config)?;
+    let messages = match file {
+        Some(file) => vec![(file.display().to_string(), lint::strip_comments(&fs::read_to_string(&file)?))],
+        None => {
+            let to = to.unwrap_or_else(|| "HEAD".to_string());
+            let range = match from {
+                Some(from) => format!("{}..{}", from, to),
+                None => to,
+            };
+            log::read_messages(&range)?
+                .into_iter()
+                .map(|(hash, message)| (hash[..hash.len().min(8)].to_string(), message))
+                .collect()
+        }
+    };
+
+    let mut clean = true;
+    for (name, message) in messages {
+        if Linter::is_ignored(&message) {
+            continue;
+        }
+        let violations = linter.lint(&message);
+        if violations.is_empty() {
+            continue;
+        }
+        clean = false;
+        println!("{} {}", name, message.lines().next().unwrap_or_default());
+        for violation in violations {
+            match violation.step {
+                Some(step) => println!("  ✖ {}: {}", step, violation.message),
+                None => println!("  ✖ {}", violation.message),
+            }
+        }
+    }
+    Ok(clean)
+}
+
+/// Prepend the changes between `from` (default: latest tag) and `to` to the changelog file
+fn write_changelog(config: &Config, from: Option<String>, to: Option<String>) -> Result<(), Box<dyn Error>> {
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+    // 从 to 之前最近的tag开始
+    let from = from.or_else(|| log::latest_tag(&format!("{}^", to)));
+    let range = match &from {
+        Some(from) => format!("{}..{}", from, to),
+        None => to.clone(),
+    };
+    let entries = changelog::entries(config, &log::read_messages(&range)?);
+    let title = if to == "HEAD" { "Unreleased" } else { &to };
+    let date = log::commit_date(&to).unwrap_or_default();
+    let section = changelog::render(config, title, &date, &entries);
+    changelog::prepend(PathBuf::from(&config.changelog.file).as_path(), &section)?;
+    println!("{}: {} entries from {}", config.changelog.file, entries.len(), range);
+    Ok(())
+}
+
+/// Compute the next version from the commits since the latest tag, then
+/// update the version files, commit and tag unless `dry_run`
+fn run_bump(config: &Config, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let settings = &config.bump;
+    let tags = log::tags();
+    let latest = bump::latest_version(tags.iter().map(String::as_str), &settings.tag_prefix);
+    let (range, current) = match latest {
+        Some((tag, version)) => (format!("{}..HEAD", tag), version),
+        None => ("HEAD".to_string(), Version::default()),
+    };
+    let reasons = bump::reasons(config, &log::read_messages(&range)?);
+    let level = match bump::release_level(&reasons) {
+        Some(level) => level,
+        None => {
+            println!("{}: no commit asks for a release", range);
+            return Ok(());
+        }
+    };
+    let next = current.bump(level);
+    let tag = format!("{}{}", settings.tag_prefix, next);
+    println!("{} -> {} ({} release, {})", current, next, level.name(), range);
+    for reason in &reasons {
+        let breaking = if reason.breaking { " [breaking]" } else { "" };
+        println!("  {:<5} {} {}{}", reason.level.name(), &reason.hash[..reason.hash.len().min(8)], reason.header, breaking);
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    // 先算出所有文件的新内容, 出错时不留下改了一半的文件
+    let mut updates: Vec<(PathBuf, String)> = vec![];
+    for file in &settings.files {
+        let content = fs::read_to_string(file)?;
+        let name = PathBuf::from(file).file_name().unwrap_or_default().to_string_lossy().to_string();
+        let updated = bump::set_version(&name, &content, &next).ok_or(format!("{}: no version to update", file))?;
+        updates.push((PathBuf::from(file), updated));
+        // Cargo.lock 中也记录了包的版本
+        let (Some(package), Some(lock)) = (bump::package_name(&content), cargo_lock(file)) else {
+            continue;
+        };
+        let index = match updates.iter().position(|(path, _)| *path == lock) {
+            Some(index) => index,
+            None => {
+                updates.push((lock.clone(), fs::read_to_string(&lock)?));
+                updates.len() - 1
+            }
+        };
+        let updated = bump::set_lock_version(&updates[index].1, &package, &next)
+            .ok_or(format!("{}: no package `{}` to update", lock.display(), package))?;
+        updates[index].1 = updated;
+    }
+    // 被忽略的文件(如 Cargo.lock)不加入提交
+    let mut staged = vec![];
+    for (path, _) in &updates {
+        if !Command::new("git").args(["check-ignore", "-q"]).arg(path).status()?.success() {
+            staged.push(path.to_string_lossy().to_string());
+        }
+    }
+    for (path, content) in &updates {
+        fs::write(path, content)?;
+    }
+    if !staged.is_empty() {
+        let mut args = vec!["add", "--"];
+        args.extend(staged.iter().map(String::as_str));
+        git(&args)?;
+    }
+    // 发布提交同样经过模板渲染
+    let template = config.template();
+    let answers: Vec<String> = template
+        .steps()
+        .iter()
+        .map(|step| {
+            settings.commit.iter().find(|(s, _)| s == step).map(|(_, answer)| answer.replace("{version}", &next.to_string())).unwrap_or_default()
+        })
+        .collect();
+    let message = template.render(&answers);
+    let mut args = vec!["commit", "--allow-empty", "-m", &message];
+    let commit_args = config.commit_args();
+    args.extend(commit_args.iter().map(String::as_str));
+    git(&args)?;
+    git(&["tag", "-a", &tag, "-m", &message])?;
+    println!("created {}", tag);
+    Ok(())
+}
+
+/// `Cargo.lock` of the package or workspace `manifest` belongs to
+fn cargo_lock(manifest: &str) -> Option<PathBuf> {
+    Path::new(manifest).parent()?.ancestors().map(|dir| dir.join("Cargo.lock")).find(|lock| lock.exists())
+}
+
+fn git(args: &[&str]) -> Result<(), Box<dyn Error>> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(format!("git {}: {}", args[0], String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    Ok(())
+}
+
+fn run_tui<W: Write>(mut out: W, app: &mut App) -> Result<Outcome, Box<dyn Error>> {
+    // setup terminal
+    enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen)?;
+    // 不捕获鼠标时可以用终端自带的方式选中复制文字
+    if app.mouse {
+        execute!(out, EnableMouseCapture)?;
+    }
+    let backend = CrosstermBackend::new(out);
+    let mut terminal = Terminal::new(backend)?;
+
     let res = run_app(&mut terminal, app);
 
     // restore terminal
@@ -145,140 +1091,179 @@ fn main() -> Result<(), Box<dyn Error>> {
     )?;
     terminal.show_cursor()?;
 
-    if let Err(err) = res {
-        println!("{:?}", err)
-    }
-
-    Ok(())
+    Ok(res?)
 }
 
-fn read_json_file() -> Result<(Vec<(String, String)>, HashMap<String, Vec<(String, String)>>), Box<dyn Error>> {
-    let f = File::open("custom.json")?;
-    let v: serde_json::Value = serde_json::from_reader(f)?;
-
-    let command_map = &v[COMMAND_KEY];
-    // 将json中的messages转成数组
-    let command_map = command_map.as_array().unwrap().iter().map(|o| {
-        o.as_object().unwrap().iter().map(|(_, value)| {
-            value.as_str()
-        }).filter(|t|{ if let Some(_) = t { true } else { false } }).collect::<Vec<_>>()
-    }).map(|o| { (o[0].unwrap().to_string(), o[1].unwrap().to_string())}).collect::<Vec<_>>();
-
-    let mut select_map = HashMap::new();
-
-    // TODO: 待优化
-    for (_, key) in &command_map {
-        let select_value = &v[key];
-        match select_value {
-            serde_json::Value::Null => {},
-            _ => {
-                let mut array: Vec<(String, String)> = vec![];
-                let mut index = 0;
-                select_value.as_array().unwrap().iter().for_each(|o| {
-                    let mut temp: (String,String) = ("".to_string(), "".to_string());
-                    o.as_object().unwrap().iter().for_each(|(key, value)|{
-                        if key == "name" {
-                            temp.0 = value.as_str().unwrap().to_string();
-                            index += 1;
-                        } else {
-                            temp.1 = value.as_str().unwrap().to_string();
-                        }
-                    });
-                    let _ = &array.push(temp);
-                });
-                select_map.insert(key.clone(), array);
+fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<Outcome> {
+    loop {
+        app.poll_lookup();
+        terminal.draw(|f| ui(f, app))?;
+        // 查询任务时定时检查结果, 界面不会卡住
+        if app.lookup.is_some() && !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        let key = match event::read()? {
+            Event::Key(key) => key,
+            Event::Mouse(event) if !app.show_help => {
+                app.handle_mouse(event);
+                continue;
             }
+            // 终端大小变化时按新的大小重新布局
+            Event::Resize(_, _) => continue,
+            _ => continue,
+        };
+        // 帮助打开时任意键关闭
+        if app.show_help {
+            app.show_help = false;
+            continue;
         }
-    }
-
-    Ok((command_map, select_map))
-}
-
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
-    loop {
-        terminal.draw(|f| ui(f, & mut app))?;
-        if let Event::Key(key) = event::read()? {
-            match app.input_mode {
-                InputMode::Type => match key.code {
-                    KeyCode::Esc => return Ok(()),
-                    KeyCode::Char(c) => {
-                        app.input.push(c);
-                    }
-                    KeyCode::Backspace => {
-                        app.input.pop();
-                    }
-                    KeyCode::Enter => {
-                        app.messages.push(app.input.drain(..).collect());
-                        if app.messages.len() >= app.command_map.len() {
-                            app.set_mode(InputMode::Confirm, &"".to_string());
-                            continue
-                        }
-                        let key = &app.command_map[app.messages.len()].1.clone();
-                        if  app.select_map.contains_key(key) {
-                            app.set_mode(InputMode::Select, key) 
-                        } else { 
-                            app.set_mode(InputMode::Type, key) 
-                        }
-                    },
-                    _ => {}
-                },
-                InputMode::Select => match key.code {
-                    KeyCode::Esc => return Ok(()),
-                    KeyCode::Left => app.state_ful_list.unselect(),
-                    KeyCode::Down => app.state_ful_list.next(),
-                    KeyCode::Up => app.state_ful_list.previous(),
-                    KeyCode::Enter => {
-                        let index = app.state_ful_list.state.selected().unwrap_or_else(|| usize::MAX);
-                        if index != usize::MAX { app.messages.push(app.state_ful_list.items[index].1.clone()); }
-                        if app.messages.len() >= app.command_map.len() { 
-                            app.set_mode(InputMode::Confirm, &"".to_string());
-                            continue
-                        }
-                        let key = &app.command_map[app.messages.len()].1.clone();
-                        if  app.select_map.contains_key(key) {
-                            app.set_mode(InputMode::Select, key) 
-                        } else { 
-                            app.set_mode(InputMode::Type, key) 
-                        }
-                    },
-                    _ => {}
-                },
-                _ => match key.code {
-                    KeyCode::Esc => return Ok(()),
-                    KeyCode::Enter => {
-                        app.commit();
-                        return Ok(())
-                    },
-                    _ => {}
+        let typed = match key.code {
+            KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => Some(c),
+            _ => None,
+        };
+        // 筛选时普通字符作为筛选文字
+        if let (Some(filter), Some(c), InputMode::Select) = (&mut app.filter, typed, app.input_mode) {
+            filter.push(c);
+            app.apply_filter();
+            continue;
+        }
+        let action = app.keymap.action(app.input_mode, &key);
+        match (action, app.input_mode) {
+            // 筛选时先关闭筛选
+            (Some(Action::Abort), InputMode::Select) if app.filter.is_some() => app.toggle_filter(),
+            (Some(Action::Abort), _) => return Ok(Outcome::Aborted),
+            (Some(Action::Help), _) => app.show_help = true,
+            (Some(Action::ToggleLog), _) => app.toggle_log_scope(),
+            (Some(Action::TogglePreview), _) => app.show_preview = !app.show_preview,
+            (Some(Action::ScrollPreview), _) => app.scroll_log(),
+            (Some(Action::Submit), InputMode::Type) => app.submit_input(),
+            (Some(Action::Previous), InputMode::Type) => app.cycle_history(true),
+            (Some(Action::Next), InputMode::Type) => app.cycle_history(false),
+            (Some(Action::Complete), InputMode::Type) => app.complete_from_history(),
+            (Some(Action::DeleteChar), InputMode::Type) => {
+                app.input.pop();
+                app.edit_input();
+            }
+            // 没有绑定的普通字符作为输入
+            (None, InputMode::Type) => {
+                if let Some(c) = typed {
+                    app.input.push(c);
+                    app.edit_input();
                 }
             }
+            (Some(Action::Filter), InputMode::Select) => app.toggle_filter(),
+            (Some(Action::DeleteChar), InputMode::Select) => {
+                if let Some(filter) = &mut app.filter {
+                    filter.pop();
+                    app.apply_filter();
+                }
+            }
+            (Some(Action::Back), InputMode::Select) => app.state_ful_list.unselect(),
+            (Some(Action::Next), InputMode::Select) => app.state_ful_list.next(),
+            (Some(Action::Previous), InputMode::Select) => app.state_ful_list.previous(),
+            (Some(Action::Submit), InputMode::Select) => app.submit_selection(),
+            (Some(Action::Submit), InputMode::Restore) => app.restore_draft(),
+            (Some(Action::Discard), InputMode::Restore) => app.discard_draft(),
+            (Some(Action::Submit), InputMode::Confirm) => return Ok(Outcome::Submitted),
+            _ => {}
         }
     }
 }
 
 fn ui<B: Backend>(f: &mut Frame<B>, app: & mut App) {
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
+    // 底部一行显示当前模式的快捷键
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
         .margin(1)
-        .constraints(
-            [
-                Constraint::Percentage(50),
-                Constraint::Percentage(50),
-            ]
-            .as_ref(),
-        )
+        .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
         .split(f.size());
 
-        render_left_area(f, chunks[0], app);
+    // 宽度不足时上下排列或只显示左侧
+    let narrow = f.size().width < app.layout.min_width;
+    let chunks = match (app.show_preview, narrow, app.layout.narrow) {
+        (false, _, _) | (true, true, NarrowLayout::Hide) => vec![rows[0]],
+        (true, true, NarrowLayout::Stack) => Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(rows[0]),
+        (true, false, _) => Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(rows[0]),
+    };
+
+    render_left_area(f, chunks[0], app);
 
-        render_right_area(f, chunks[1]);
+    match chunks.get(1) {
+        Some(&chunk) => render_right_area(f, chunk, app),
+        None => app.log_area = Rect::default(),
+    }
+
+    render_status_bar(f, rows[1], app);
+
+    if app.show_help {
+        render_help(f, app);
+    }
+}
+
+/// `↑/↓` for the keys of one action
+fn keys_text(chords: &[KeyChord]) -> String {
+    chords.iter().map(KeyChord::to_string).collect::<Vec<_>>().join("/")
+}
+
+fn render_status_bar<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect, app: &App) {
+    let mut spans = vec![];
+    for (action, chords) in app.keymap.bindings(app.input_mode) {
+        if !spans.is_empty() {
+            spans.push(Span::raw("  "));
+        }
+        spans.push(Span::styled(keys_text(&chords), app.theme.key));
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(action.description(app.input_mode, app.text), app.theme.dim));
+    }
+    f.render_widget(Paragraph::new(Spans::from(spans)), chunk);
+}
+
+// 居中的弹窗, 列出当前模式的全部快捷键
+fn render_help<B: Backend>(f: &mut Frame<B>, app: &App) {
+    let bindings = app.keymap.bindings(app.input_mode);
+    let keys: Vec<String> = bindings.iter().map(|(_, chords)| keys_text(chords)).collect();
+    let key_width = keys.iter().map(|k| k.width()).max().unwrap_or(0);
+    let mut lines: Vec<Spans> = bindings
+        .iter()
+        .zip(&keys)
+        .map(|((action, _), keys)| {
+            Spans::from(vec![
+                Span::styled(format!("{:<width$}", keys, width = key_width), app.theme.key),
+                Span::raw("  "),
+                Span::raw(action.description(app.input_mode, app.text)),
+            ])
+        })
+        .collect();
+    lines.push(Spans::from(""));
+    lines.push(Spans::from(Span::styled(app.text.help_close, app.theme.dim)));
+
+    let area = f.size();
+    let width = lines.iter().map(Spans::width).max().unwrap_or(0) as u16 + 4;
+    let height = lines.len() as u16 + 2;
+    let rect = tui::layout::Rect {
+        x: area.width.saturating_sub(width) / 2,
+        y: area.height.saturating_sub(height) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+    let help = Paragraph::new(lines)
+        .block(Block::default().title(app.text.help_title).borders(Borders::ALL));
+    f.render_widget(Clear, rect);
+    f.render_widget(help, rect);
 }
 
 fn render_left_area<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect, app: & mut App) {
     match app.input_mode {
         InputMode::Select => render_select(f, chunk, app),
         InputMode::Type => render_input(f, chunk, app),
-        InputMode::Confirm => render_confirm(f, chunk, app)
+        InputMode::Confirm => render_confirm(f, chunk, app),
+        InputMode::Restore => render_restore(f, chunk, app),
     }
 }
 
@@ -290,51 +1275,141 @@ fn render_input<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect, app: &App
         .constraints([Constraint::Length(1), Constraint::Length(3), Constraint::Min(1)].as_ref())
         .split(chunk);
 
-        // 渲染提示
-        let text = Text::from(text);
+        // 渲染提示, 后面附上警告
+        let mut text = vec![Span::raw(text)];
+        if let Some(notice) = &app.notice {
+            text.push(Span::raw("  "));
+            text.push(Span::styled(notice.as_str(), app.theme.warning));
+        }
+        let text = Text::from(Spans::from(text));
         let help_message = Paragraph::new(text);
         f.render_widget(help_message, chunk[0]);
 
         // 渲染input框
-        let input = Paragraph::new(app.input.as_ref())
-        .block(Block::default().borders(Borders::ALL).title("Input"));
+        // emoji按终端中的实际宽度显示
+        let input = Paragraph::new(display::normalize(&app.input))
+        .block(Block::default().borders(Borders::ALL).title(app.text.input_title));
 
         f.render_widget(input, chunk[1]);
 
         // 设置input光标
         f.set_cursor(
             // Put cursor past the end of the input text
-            chunk[1].x + app.input.width() as u16 + 1,
+            chunk[1].x + display::width(&app.input) as u16 + 1,
             // Move one line down, from the border to the input line
             chunk[1].y + 1,
         );
 
         // 展示输入信息
         let messages: Vec<ListItem> = app.messages.iter().enumerate().map(|(i , m)| {
-            let content = Spans::from(Span::raw(format!("{}: {}", i, m)));
+            let content = Spans::from(Span::raw(display::normalize(&format!("{}: {}", i, m))));
             ListItem::new(content)
         }).collect();
 
-        let messages = List::new(messages).block(Block::default().title("Messages").borders(Borders::ALL));
+        let messages = List::new(messages).block(Block::default().title(app.text.messages_title).borders(Borders::ALL));
 
         f.render_widget(messages, chunk[2]);
 }
 
 fn render_select<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect, app: & mut App) {
-    let items: Vec<ListItem> = app.state_ful_list.items.iter().map(|i| {
-        let content = Spans::from(Span::raw(format!("{}", i.0)));
-        ListItem::new(content).style(Style::default().fg(Color::Black).bg(Color::White))
+    let key = app.command_map.get(app.messages.len()).map(|(_, key)| key.as_str());
+    // 选中项的说明显示在列表下方, 最多占三分之一高度
+    let description = app.state_ful_list.selected().zip(key).and_then(|((_, value), key)| app.descriptions.get(key)?.get(value));
+    let (chunk, details) = match description {
+        Some(description) => {
+            let lines = display::wrap(description, chunk.width.saturating_sub(2) as usize);
+            let height = (lines.len() as u16 + 2).min(chunk.height / 3).max(3);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(1), Constraint::Length(height)].as_ref())
+                .split(chunk);
+            (chunks[0], Some((chunks[1], lines)))
+        }
+        None => (chunk, None),
+    };
+
+    // 自己维护滚动位置, 只把可见的部分交给tui
+    let height = chunk.height.saturating_sub(2) as usize;
+    app.state_ful_list.scroll_into_view(height, item_height);
+    app.list_area = chunk;
+    // 去掉边框和 ">> " 后一行的宽度, 过长的名称以省略号结尾
+    let width = chunk.width.saturating_sub(2 + 3) as usize;
+    let list = &app.state_ful_list;
+    let items: Vec<ListItem> = list.visible.iter().skip(list.offset).map(|&index| {
+        let i = &list.items[index];
+        let suggestion = app.suggestions.iter().find(|s| key == Some(SCOPES) && s.scope == i.1);
+        // 名称中的换行显示为多行
+        let mut lines: Vec<Spans> = vec![];
+        for line in i.0.split('\n') {
+            let line = display::ellipsize(line, width);
+            let mut content = vec![Span::raw(display::normalize(&line))];
+            // 第一行标出触发建议的暂存文件, 放不下时省略
+            if let Some(suggestion) = suggestion.filter(|_| lines.is_empty()) {
+                let marker = format!("  * {}", suggestion_files(&suggestion.files));
+                if display::width(&line) + display::width(&marker) <= width {
+                    content.push(Span::styled(marker, app.theme.suggestion));
+                }
+            }
+            lines.push(Spans::from(content));
+        }
+        let style = if CommandSource::is_error(i) { app.theme.error } else { app.theme.item };
+        ListItem::new(lines).style(style)
     }).collect();
 
-    let items = List::new(items).block(Block::default().title("Selected").borders(Borders::ALL))
-        .highlight_style(Style::default().bg(Color::LightGreen).add_modifier(Modifier::BOLD))
+    let title = match &app.filter {
+        Some(filter) => format!("{} - {}", app.text.select_title, i18n::fill(app.text.filter_title, &[("filter", filter)])),
+        None => app.text.select_title.to_string(),
+    };
+    let items = List::new(items).block(Block::default().title(title).borders(Borders::ALL))
+        .highlight_style(app.theme.highlight)
         .highlight_symbol(">> ");
 
-    f.render_stateful_widget(items, chunk,  & mut app.state_ful_list.state);
+    let mut state = ListState::default();
+    state.select(list.state.selected().and_then(|i| i.checked_sub(list.offset)));
+    f.render_stateful_widget(items, chunk, &mut state);
+
+    if let Some((area, lines)) = details {
+        let lines: Vec<Spans> = lines.into_iter().map(|line| Spans::from(display::normalize(&line))).collect();
+        let details = Paragraph::new(lines).block(Block::default().title(app.text.details_title).borders(Borders::ALL));
+        f.render_widget(details, area);
+    }
+}
+
+/// Rows a choice takes in the select list, one per line of its name
+fn item_height((name, _): &(String, String)) -> usize {
+    name.split('\n').count()
+}
+
+/// Whether the cell at `column`, `row` lies in `area`
+fn contains(area: Rect, column: u16, row: u16) -> bool {
+    (area.x..area.x + area.width).contains(&column) && (area.y..area.y + area.height).contains(&row)
+}
+
+/// `a.rs, b.rs +3`
+fn suggestion_files(files: &[String]) -> String {
+    const SHOWN: usize = 2;
+    let mut text = files.iter().take(SHOWN).map(String::as_str).collect::<Vec<_>>().join(", ");
+    if files.len() > SHOWN {
+        text.push_str(&format!(" +{}", files.len() - SHOWN));
+    }
+    text
+}
+
+/// Key hint such as `Press {Esc} to quit`, with the keys in braces drawn bold
+fn hint(text: &str, key: Style) -> Spans<'_> {
+    let mut spans = vec![];
+    for (i, part) in text.split(['{', '}']).enumerate() {
+        if i % 2 == 1 {
+            spans.push(Span::styled(part, key));
+        } else if !part.is_empty() {
+            spans.push(Span::raw(part));
+        }
+    }
+    Spans::from(spans)
 }
 
 fn render_confirm<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect, app: & mut App) {
-    let block = Block::default().title("Confirm").borders(Borders::ALL);
+    let block = Block::default().title(app.text.confirm_title).borders(Borders::ALL);
 
     f.render_widget(block, chunk);
 
@@ -344,26 +1419,27 @@ fn render_confirm<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect, app: &
         .constraints([Constraint::Min(1)].as_ref())
         .split(chunk);
 
-    let text = app.messages.join(" ");
-    let text1 = Text::from(vec![Spans::from(vec![
-        Span::raw("commit message: "),
-        Span::raw(text),
-    ])]);
-    let text2 = Text::from(vec![Spans::from(vec![
-        Span::raw("Press "),
-        Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(" to quit, "),
-        Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(" to commit the message"),
-    ])]);
+    let text = display::normalize(&app.message());
+    let mut lines = text.lines();
+    let mut text1 = vec![Spans::from(vec![
+        Span::raw(app.text.commit_message),
+        Span::raw(lines.next().unwrap_or_default()),
+    ])];
+    text1.extend(lines.map(Spans::from));
+    // git commit --signoff 添加的尾注
+    if let Some(signoff) = &app.signoff {
+        text1.push(Spans::from(""));
+        text1.push(Spans::from(Span::styled(signoff.as_str(), app.theme.dim)));
+    }
+    let text1 = Text::from(text1);
+    let text2 = Text::from(hint(app.text.confirm_hint, app.theme.key));
     let commit_message = List::new(vec![ListItem::new(text2), ListItem::new(Span::raw("")), ListItem::new(text1)]);
     f.render_widget(commit_message, chunk[0]);
     
 }
 
-fn render_right_area<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect) {
-
-    let block = Block::default().title("git log").borders(Borders::ALL);
+fn render_restore<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect, app: &App) {
+    let block = Block::default().title(app.text.draft_title).borders(Borders::ALL);
 
     f.render_widget(block, chunk);
 
@@ -373,19 +1449,91 @@ fn render_right_area<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect) {
         .constraints([Constraint::Min(1)].as_ref())
         .split(chunk);
 
-    let output = Command::new("git").args(["log", "-n2"]).output().expect("failed to execute process");
+    let mut items = vec![
+        ListItem::new(hint(app.text.draft_hint, app.theme.key)),
+        ListItem::new(Span::raw("")),
+    ];
+    if let Some(draft) = &app.draft {
+        for (step, answer) in app.template.steps().iter().zip(draft.answers_for(app.template.steps())) {
+            items.push(ListItem::new(Span::raw(format!("{}: {}", step, answer))));
+        }
+    }
+    f.render_widget(List::new(items), chunk[0]);
+}
+
+fn render_right_area<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect, app: & mut App) {
+    app.log_area = chunk;
 
-    let stdout  = String::from_utf8(output.stdout);
-    let stdout  = stdout.unwrap_or_default();
-    let stdout  = stdout.split('\n').collect::<Vec<&str>>();
-    let stdout: Vec<ListItem> = stdout.iter().enumerate().map(|(i, m)| {
-        let content = Spans::from(Span::raw(format!("  {}", m)));
-        ListItem::new(content).style(
-            if i % 6 == 0 { Style::default().fg(Color::Rgb(193, 156, 0)) } else {Style::default()}
-        )
+    // 已验证的任务显示在git log上方
+    let chunk = match &app.ticket {
+        Some(ticket @ (Ticket::Found { .. } | Ticket::Unavailable { .. })) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(4), Constraint::Min(1)].as_ref())
+                .split(chunk);
+            render_ticket(f, chunks[0], ticket, app);
+            chunks[1]
+        }
+        _ => chunk,
+    };
+
+    let scope = match app.log_config.scope {
+        LogScope::All => app.text.log_scope_all,
+        // 没有上游时read_log显示全部提交
+        LogScope::Branch if !app.upstream => app.text.log_scope_no_upstream,
+        LogScope::Branch => app.text.log_scope_branch,
+    };
+    let key = keys_text(&app.keymap.keys(app.input_mode, Action::ToggleLog));
+    let title = i18n::fill(app.text.log_title, &[("scope", scope), ("key", &key)]);
+    let block = Block::default().title(title).borders(Borders::ALL);
+
+    f.render_widget(block, chunk);
+
+    let chunk = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(1)].as_ref())
+        .split(chunk);
+
+    let entries: Vec<ListItem> = app.log.iter().skip(app.log_offset).map(|entry| {
+        // 第一行: hash 时间 作者
+        let meta = Spans::from(vec![
+            Span::styled(entry.hash.as_str(), app.theme.hash),
+            Span::raw(" "),
+            Span::styled(entry.date.as_str(), app.theme.dim),
+            Span::raw(" "),
+            Span::raw(entry.author.as_str()),
+        ]);
+        // 第二行: 提交标题, 高亮已配置的type/scope
+        let header = Spans::from(
+            log::highlight(&entry.header, &app.known_values).into_iter().map(|(token, known)| {
+                if known {
+                    Span::styled(token, app.theme.known)
+                } else {
+                    Span::raw(token)
+                }
+            }).collect::<Vec<_>>()
+        );
+        ListItem::new(vec![meta, header, Spans::from("")])
     }).collect();
 
-    let stdout = List::new(stdout);
+    let entries = List::new(entries);
 
-    f.render_widget(stdout, chunk[0]);
-}
\ No newline at end of file
+    f.render_widget(entries, chunk[0]);
+}
+
+fn render_ticket<B: Backend>(f: &mut Frame<B>, chunk:tui::layout::Rect, ticket: &Ticket, app: &App) {
+    let catalog = app.text;
+    let (id, text) = match ticket {
+        Ticket::Found { id, title } => (id, Span::raw(title.as_str())),
+        Ticket::Unavailable { id, reason } => {
+            let text = i18n::fill(catalog.ticket_not_checked, &[("reason", reason)]);
+            (id, Span::styled(text, app.theme.warning))
+        }
+        Ticket::NotFound { id } => (id, Span::styled(catalog.ticket_not_found, app.theme.error)),
+    };
+    let paragraph = Paragraph::new(Spans::from(text))
+        .block(Block::default().title(i18n::fill(catalog.ticket_title, &[("id", id)])).borders(Borders::ALL))
+        .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, chunk);
+}
@@ -0,0 +1,232 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    config::Config,
+    conventional::ConventionalCommit,
+    template::{CHANGE_TYPE, SUBJECT},
+};
+
+/// A `major.minor.patch` version, pre-release tags are not bumped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    /// Parse a tag such as `v1.2.3` with the given prefix
+    pub fn from_tag(tag: &str, prefix: &str) -> Option<Version> {
+        let mut parts = tag.strip_prefix(prefix)?.split('.');
+        let version = Version {
+            major: parts.next()?.parse().ok()?,
+            minor: parts.next()?.parse().ok()?,
+            patch: parts.next()?.parse().ok()?,
+        };
+        match parts.next() {
+            Some(_) => None,
+            None => Some(version),
+        }
+    }
+
+    pub fn bump(self, level: Level) -> Version {
+        match level {
+            Level::Major => Version { major: self.major + 1, minor: 0, patch: 0 },
+            Level::Minor => Version { minor: self.minor + 1, patch: 0, ..self },
+            Level::Patch => Version { patch: self.patch + 1, ..self },
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Level {
+    pub fn from_name(name: &str) -> Option<Level> {
+        match name {
+            "major" => Some(Level::Major),
+            "minor" => Some(Level::Minor),
+            "patch" => Some(Level::Patch),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Major => "major",
+            Level::Minor => "minor",
+            Level::Patch => "patch",
+        }
+    }
+}
+
+/// Settings of `rcz bump`
+#[derive(Debug, Clone)]
+pub struct BumpConfig {
+    pub tag_prefix: String,
+    /// Level each ChangeType value asks for, values not listed do not bump
+    pub levels: HashMap<String, Level>,
+    /// Files whose version is rewritten (`Cargo.toml`, `package.json`)
+    pub files: Vec<String>,
+    /// Answer per step type for the release commit, `{version}` is replaced
+    pub commit: Vec<(String, String)>,
+}
+
+impl Default for BumpConfig {
+    fn default() -> Self {
+        BumpConfig {
+            tag_prefix: "v".to_string(),
+            levels: [("feat", Level::Minor), ("fix", Level::Patch), ("perf", Level::Patch)]
+                .iter()
+                .map(|(value, level)| (value.to_string(), *level))
+                .collect(),
+            files: vec![],
+            commit: vec![
+                (CHANGE_TYPE.to_string(), "chore".to_string()),
+                (SUBJECT.to_string(), "release {version}".to_string()),
+            ],
+        }
+    }
+}
+
+/// Why a commit takes part in the bump
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reason {
+    pub hash: String,
+    pub header: String,
+    pub level: Level,
+    pub breaking: bool,
+}
+
+/// Level asked for by every commit that bumps the version, see [`release_level`]
+pub fn reasons(config: &Config, messages: &[(String, String)]) -> Vec<Reason> {
+    let template = config.template();
+    let change_type = template.steps().iter().position(|s| s == CHANGE_TYPE);
+    messages
+        .iter()
+        .filter_map(|(hash, message)| {
+            let header = message.lines().next().unwrap_or_default().to_string();
+            if is_breaking(message) {
+                return Some(Reason { hash: hash.clone(), header, level: Level::Major, breaking: true });
+            }
+            let answers = template.parse(message, &config.select_map);
+            let value = change_type.map(|i| answers[i].as_str())?;
+            let level = *config.bump.levels.get(value)?;
+            Some(Reason { hash: hash.clone(), header, level, breaking: false })
+        })
+        .collect()
+}
+
+/// The release bumps by the highest level any commit asks for
+pub fn release_level(reasons: &[Reason]) -> Option<Level> {
+    reasons.iter().map(|reason| reason.level).max()
+}
+
+/// `BREAKING CHANGE` trailer or a conventional `!` marker
+fn is_breaking(message: &str) -> bool {
+    message.lines().any(|line| line.starts_with("BREAKING CHANGE: ") || line.starts_with("BREAKING-CHANGE: "))
+        || message.parse::<ConventionalCommit>().is_ok_and(|commit| commit.is_breaking())
+}
+
+/// Highest tagged version with the configured prefix
+pub fn latest_version<'a>(tags: impl Iterator<Item = &'a str>, prefix: &str) -> Option<(&'a str, Version)> {
+    tags.filter_map(|tag| Version::from_tag(tag, prefix).map(|version| (tag, version)))
+        .max_by(|(_, a), (_, b)| a.cmp(b))
+}
+
+/// Rewrite the version in `Cargo.toml` (`[package]` section) or `package.json`;
+/// `None` when the file has no version to replace
+pub fn set_version(file_name: &str, content: &str, version: &Version) -> Option<String> {
+    let mut in_package = !file_name.ends_with(".toml");
+    let mut replaced = false;
+    let lines: Vec<String> = content
+        .split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if file_name.ends_with(".toml") && trimmed.starts_with('[') {
+                in_package = trimmed.starts_with("[package]");
+            }
+            if replaced || !in_package {
+                return line.to_string();
+            }
+            let is_version = if file_name.ends_with(".json") {
+                trimmed.starts_with("\"version\"")
+            } else {
+                trimmed.starts_with("version") && trimmed["version".len()..].trim_start().starts_with('=')
+            };
+            // 替换最后一对引号中的版本号
+            let end = line.rfind('"');
+            let start = end.and_then(|end| line[..end].rfind('"'));
+            match (is_version, start, end) {
+                (true, Some(start), Some(end)) => {
+                    replaced = true;
+                    format!("{}{}{}", &line[..start + 1], version, &line[end..])
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect();
+    if replaced {
+        Some(lines.concat())
+    } else {
+        None
+    }
+}
+
+/// `name` in the `[package]` section of a `Cargo.toml`
+pub fn package_name(manifest: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_package = trimmed.starts_with("[package]");
+        } else if in_package {
+            if let Some(value) = trimmed.strip_prefix("name").map(str::trim_start).and_then(|rest| rest.strip_prefix('=')) {
+                return Some(value.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Rewrite the version of the local package `name` in a `Cargo.lock`;
+/// `None` when the lock has no such package
+pub fn set_lock_version(lock: &str, name: &str, version: &Version) -> Option<String> {
+    let name_line = format!("name = \"{}\"", name);
+    // 按 [[package]] 分段, 只改没有 source 的本地包
+    let mut blocks: Vec<Vec<&str>> = vec![vec![]];
+    for line in lock.split_inclusive('\n') {
+        if line.trim() == "[[package]]" {
+            blocks.push(vec![]);
+        }
+        blocks.last_mut()?.push(line);
+    }
+    let mut replaced = false;
+    let mut lines = vec![];
+    for block in blocks {
+        let local = block.iter().any(|line| line.trim() == name_line) && !block.iter().any(|line| line.starts_with("source = "));
+        for line in block {
+            if local && !replaced && line.starts_with("version = ") {
+                replaced = true;
+                lines.push(format!("version = \"{}\"{}", version, if line.ends_with('\n') { "\n" } else { "" }));
+            } else {
+                lines.push(line.to_string());
+            }
+        }
+    }
+    if replaced {
+        Some(lines.concat())
+    } else {
+        None
+    }
+}
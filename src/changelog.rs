@@ -0,0 +1,124 @@
+use std::{fs, io, path::Path};
+
+use crate::{
+    config::Config,
+    template::{Template, CHANGE_TYPE, MISSION_ID, SCOPES, SUBJECT},
+};
+
+const TITLE: &str = "# Changelog";
+
+/// One commit as listed in the changelog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub hash: String,
+    pub change_type: String,
+    pub scope: String,
+    pub subject: String,
+    pub mission_id: String,
+}
+
+/// Parse `(hash, message)` pairs with the configured template, dropping the
+/// commits without a known ChangeType
+pub fn entries(config: &Config, messages: &[(String, String)]) -> Vec<Entry> {
+    let template = config.template();
+    let steps = template.steps();
+    let position = |step: &str| steps.iter().position(|s| s == step);
+    let subject = position(SUBJECT).or_else(|| steps.iter().rposition(|s| Template::is_header(s)));
+    let (change_type, scope, mission_id) = (position(CHANGE_TYPE), position(SCOPES), position(MISSION_ID));
+    let answer = |answers: &[String], i: Option<usize>| i.map(|i| answers[i].clone()).unwrap_or_default();
+
+    messages
+        .iter()
+        .map(|(hash, message)| {
+            let answers = template.parse(message, &config.select_map);
+            Entry {
+                hash: hash.clone(),
+                change_type: answer(&answers, change_type),
+                scope: answer(&answers, scope),
+                subject: answer(&answers, subject),
+                mission_id: answer(&answers, mission_id),
+            }
+        })
+        .filter(|entry| !entry.change_type.is_empty())
+        .collect()
+}
+
+/// Render one release section, grouped by ChangeType and then by scope
+pub fn render(config: &Config, title: &str, date: &str, entries: &[Entry]) -> String {
+    let settings = &config.changelog;
+    let choices = config.select_map.get(CHANGE_TYPE).cloned().unwrap_or_default();
+    let mut types: Vec<&str> = settings.order.iter().map(String::as_str).collect();
+    types.extend(choices.iter().map(|(_, value)| value.as_str()).filter(|value| !settings.order.iter().any(|o| o == value)));
+
+    let mut out = format!("## {} ({})\n", title, date);
+    for change_type in types {
+        if settings.hidden.iter().any(|hidden| hidden == change_type) {
+            continue;
+        }
+        let group: Vec<&Entry> = entries.iter().filter(|e| e.change_type == change_type).collect();
+        if group.is_empty() {
+            continue;
+        }
+        let heading = choices
+            .iter()
+            .find(|(_, value)| value == change_type)
+            .map(|(name, _)| section_title(name))
+            .unwrap_or_else(|| change_type.to_string());
+        out.push_str(&format!("\n### {}\n", heading));
+
+        // 没有scope的条目放在最前面, 其余按scope首次出现的顺序分组
+        let mut scopes: Vec<&str> = vec![];
+        for entry in &group {
+            if !scopes.contains(&entry.scope.as_str()) {
+                scopes.push(&entry.scope);
+            }
+        }
+        scopes.sort_by_key(|scope| !scope.is_empty());
+        for scope in scopes {
+            if scope.is_empty() {
+                out.push('\n');
+            } else {
+                out.push_str(&format!("\n#### {}\n\n", scope));
+            }
+            for entry in group.iter().filter(|e| e.scope == scope) {
+                out.push_str(&format!("- {}{} ({})\n", entry.subject, mission_link(config, &entry.mission_id), short(&entry.hash)));
+            }
+        }
+    }
+    out
+}
+
+/// First line of a choice `name` with the alignment spaces collapsed
+fn section_title(name: &str) -> String {
+    name.lines().next().unwrap_or_default().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn mission_link(config: &Config, id: &str) -> String {
+    if id.is_empty() {
+        return String::new();
+    }
+    match &config.changelog.mission_url {
+        Some(url) => format!(" ([{}]({}))", id, url.replace("{id}", id)),
+        None => format!(" ({})", id),
+    }
+}
+
+fn short(hash: &str) -> &str {
+    &hash[..hash.len().min(8)]
+}
+
+/// Insert `section` above the previous releases of the changelog at `path`
+pub fn prepend(path: &Path, section: &str) -> io::Result<()> {
+    let existing = match fs::read_to_string(path) {
+        Ok(existing) => existing,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    let rest = existing.strip_prefix(TITLE).unwrap_or(&existing).trim_start_matches('\n');
+    let mut content = format!("{}\n\n{}", TITLE, section);
+    if !rest.is_empty() {
+        content.push('\n');
+        content.push_str(rest);
+    }
+    fs::write(path, content)
+}
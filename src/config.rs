@@ -0,0 +1,448 @@
+use std::{collections::HashMap, error::Error, fs::File, path::Path};
+
+use serde_json::Value;
+
+use crate::{
+    bump::{BumpConfig, Level},
+    gitmoji::{self, EmojiFormat},
+    history::SelectDefault,
+    i18n::Locale,
+    keymap::Keymap,
+    log::{LogConfig, LogScope},
+    scope::ScopeRule,
+    source::CommandSource,
+    template::Template,
+    theme::Theme,
+    tracker::TrackerConfig,
+    workspace::{self, ScopeSource},
+};
+
+pub const CONFIG_FILE: &str = "custom.json";
+const COMMAND_KEY: &str = "messages";
+const LOG_KEY: &str = "log";
+const RULES_KEY: &str = "rules";
+const CHANGELOG_KEY: &str = "changelog";
+const BUMP_KEY: &str = "bump";
+
+/// Settings read from custom.json
+#[derive(Debug, Default)]
+pub struct Config {
+    /// (placeholder, type) of every step, in commit order
+    pub command_map: Vec<(String, String)>,
+    /// (name, value) choices of the select steps, keyed by step type
+    pub select_map: HashMap<String, Vec<(String, String)>>,
+    /// (command line flag, step type): `--<kebab-case type>` plus each step's `alias`
+    pub flags: Vec<(String, String)>,
+    pub log: LogConfig,
+    pub rules: Rules,
+    pub changelog: ChangelogConfig,
+    pub bump: BumpConfig,
+    /// Always pass `--signoff` to `git commit`
+    pub signoff: bool,
+    /// Always pass `-S` to `git commit`
+    pub gpg_sign: bool,
+    /// Choice a select list starts on when there is nothing to pre-fill
+    pub select_default: SelectDefault,
+    /// Glob patterns of staged paths that suggest a `Scopes` value
+    pub scope_rules: Vec<ScopeRule>,
+    /// Language of the UI, `locale` in the config or detected from the environment
+    pub locale: Locale,
+    /// Styles of the widgets, `theme` preset plus overrides
+    pub theme: Theme,
+    /// Emoji written before a select value, keyed by step type and value
+    pub emoji: HashMap<String, HashMap<String, String>>,
+    /// Commands filling select lists, keyed by step type; only run by
+    /// [`Config::load_sources`] for the steps that are shown, removed once they ran
+    pub command_sources: HashMap<String, CommandSource>,
+    /// Long description of a choice shown under the list, keyed by step type and value
+    pub descriptions: HashMap<String, HashMap<String, String>>,
+    /// Issue tracker the `MissionId` step is validated against
+    pub tracker: Option<TrackerConfig>,
+    /// Key bindings, `keys` preset plus per-action overrides
+    pub keys: Keymap,
+    /// Capture the mouse for clicks and the wheel; `"mouse": false` keeps the
+    /// terminal's own text selection
+    pub mouse: bool,
+    pub layout: LayoutConfig,
+}
+
+/// Checks `rcz lint` applies on top of the select lists
+#[derive(Debug, Default, Clone)]
+pub struct Rules {
+    /// Maximum number of characters in the first line
+    pub header_max_length: Option<usize>,
+    /// Regular expression the `MissionId` step must match
+    pub mission_id_pattern: Option<String>,
+    /// Steps that may not be empty, every header step when not configured
+    pub required: Option<Vec<String>>,
+}
+
+/// How the two panes are arranged
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutConfig {
+    /// Below this many columns the panes no longer sit side by side
+    pub min_width: u16,
+    pub narrow: NarrowLayout,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        LayoutConfig { min_width: 100, narrow: NarrowLayout::default() }
+    }
+}
+
+/// Layout of a terminal narrower than `layout.minWidth`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NarrowLayout {
+    /// The git log goes below the prompt
+    #[default]
+    Stack,
+    /// Only the prompt is shown
+    Hide,
+}
+
+/// Settings of `rcz changelog`
+#[derive(Debug, Clone)]
+pub struct ChangelogConfig {
+    pub file: String,
+    /// ChangeType values listed first, in this order; the others follow in config order
+    pub order: Vec<String>,
+    /// ChangeType values left out of the changelog
+    pub hidden: Vec<String>,
+    /// Link for mission IDs, `{id}` is replaced by the ID
+    pub mission_url: Option<String>,
+}
+
+impl Default for ChangelogConfig {
+    fn default() -> Self {
+        ChangelogConfig {
+            file: "CHANGELOG.md".to_string(),
+            order: vec![],
+            hidden: vec![],
+            mission_url: None,
+        }
+    }
+}
+
+/// `MissionId` -> `mission-id`
+fn kebab_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            out.push('-');
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+/// Append the choices whose value is not in `array` yet
+fn merge(array: &mut Vec<(String, String)>, choices: Vec<(String, String)>) {
+    for choice in choices {
+        if !array.iter().any(|(_, value)| *value == choice.1) {
+            array.push(choice);
+        }
+    }
+}
+
+fn strings(v: &Value) -> Option<Vec<String>> {
+    v.as_array().map(|items| items.iter().filter_map(|s| s.as_str()).map(str::to_string).collect())
+}
+
+impl Config {
+    pub fn load() -> Result<Config, Box<dyn Error>> {
+        let f = File::open(CONFIG_FILE)?;
+        let v: Value = serde_json::from_reader(f)?;
+        Config::from_value(&v)
+    }
+
+    pub fn from_value(v: &Value) -> Result<Config, Box<dyn Error>> {
+        let locale = match v["locale"].as_str() {
+            Some(tag) => Locale::from_tag(tag).ok_or("custom.json: `locale` must be \"zh-CN\" or \"en\"")?,
+            None => Locale::detect(),
+        };
+        // 将json中的messages转成数组, 按index排序
+        let mut steps = v[COMMAND_KEY]
+            .as_array()
+            .ok_or("custom.json: `messages` must be an array")?
+            .iter()
+            .map(|o| {
+                let index = o["index"].as_u64().unwrap_or(u64::MAX);
+                let placeholder = locale.pick(&o["placeholder"]).unwrap_or_default();
+                let key = o["type"].as_str().ok_or("custom.json: every message needs a `type`")?.to_string();
+                let aliases = match &o["alias"] {
+                    Value::String(alias) => vec![alias.clone()],
+                    alias => strings(alias).unwrap_or_default(),
+                };
+                let source = match &o["source"] {
+                    Value::Null => None,
+                    source => Some(CommandSource::from_value(source).ok_or_else(|| {
+                        format!("custom.json: `source` of `{}` needs a non-empty `command` array", key)
+                    })?),
+                };
+                Ok((index, (placeholder, key), aliases, source))
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+        steps.sort_by_key(|(index, _, _, _)| *index);
+        let mut flags = vec![];
+        for (_, (_, key), aliases, _) in &steps {
+            flags.push((format!("--{}", kebab_case(key)), key.clone()));
+            flags.extend(aliases.iter().map(|alias| (alias.clone(), key.clone())));
+        }
+        let mut command_sources = HashMap::new();
+        let mut command_map = vec![];
+        for (_, (placeholder, key), _, source) in steps {
+            if let Some(source) = source {
+                command_sources.insert(key.clone(), source);
+            }
+            command_map.push((placeholder, key));
+        }
+
+        let emoji_format = match v["gitmoji"]["format"].as_str() {
+            Some(name) => EmojiFormat::from_name(name).ok_or("custom.json: `gitmoji.format` must be \"code\" or \"unicode\"")?,
+            None => EmojiFormat::default(),
+        };
+        let mut emoji = HashMap::new();
+        let mut descriptions = HashMap::new();
+        let mut select_map = HashMap::new();
+        for (_, key) in &command_map {
+            let mut step_emoji = HashMap::new();
+            let mut step_descriptions = HashMap::new();
+            match &v[key] {
+                Value::Array(choices) => {
+                    select_map.insert(key.clone(), Config::choices(choices, locale, emoji_format, &mut step_emoji, &mut step_descriptions));
+                }
+                // {"source": {"command": [...]}}: 运行命令生成选项
+                Value::Object(o) if o.get("source").is_some_and(Value::is_object) => {
+                    let source = CommandSource::from_value(&o["source"]).ok_or_else(|| {
+                        format!("custom.json: `{}.source` needs a non-empty `command` array", key)
+                    })?;
+                    command_sources.insert(key.clone(), source);
+                    let choices = o.get("choices").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+                    // 命令在显示列表时才运行, 之前只有静态选项
+                    if !choices.is_empty() {
+                        select_map.insert(key.clone(), Config::choices(choices, locale, emoji_format, &mut step_emoji, &mut step_descriptions));
+                    }
+                }
+                // {"source": "cargo-workspace", "choices": [...]}: 静态选项在前, 再合并发现的选项
+                Value::Object(o) => {
+                    let choices = o.get("choices").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+                    let mut array = Config::choices(choices, locale, emoji_format, &mut step_emoji, &mut step_descriptions);
+                    let names = match o.get("source") {
+                        Some(Value::String(name)) => vec![name.clone()],
+                        Some(names) => strings(names).unwrap_or_default(),
+                        None => vec![],
+                    };
+                    for name in names {
+                        // 内置的gitmoji列表
+                        if name == "gitmoji" {
+                            merge(&mut array, gitmoji::choices(emoji_format));
+                            continue;
+                        }
+                        let directories = o.get("directories").and_then(Value::as_str);
+                        let source = ScopeSource::from_name(&name, directories).ok_or_else(|| {
+                            format!("custom.json: `{}.source` must be \"cargo-workspace\", \"npm-workspaces\", \"gitmoji\" or \"directories\" (with `directories`)", key)
+                        })?;
+                        let discovered = workspace::discover(&source, Path::new("."))
+                            .map_err(|err| format!("custom.json: `{}.source` {}: {}", key, name, err))?;
+                        merge(&mut array, discovered);
+                    }
+                    select_map.insert(key.clone(), array);
+                }
+                _ => {}
+            }
+            if !step_emoji.is_empty() {
+                emoji.insert(key.clone(), step_emoji);
+            }
+            if !step_descriptions.is_empty() {
+                descriptions.insert(key.clone(), step_descriptions);
+            }
+        }
+        let mut log = LogConfig::default();
+        if let Some(count) = v[LOG_KEY]["count"].as_u64() {
+            log.count = count as usize;
+        }
+        if let Some(scope) = v[LOG_KEY]["scope"].as_str() {
+            log.scope = LogScope::from_name(scope).ok_or("custom.json: `log.scope` must be \"all\" or \"branch\"")?;
+        }
+
+        let r = &v[RULES_KEY];
+        let rules = Rules {
+            header_max_length: r["headerMaxLength"].as_u64().map(|n| n as usize),
+            mission_id_pattern: r["missionIdPattern"].as_str().map(str::to_string),
+            required: strings(&r["required"]),
+        };
+
+        let c = &v[CHANGELOG_KEY];
+        let mut changelog = ChangelogConfig::default();
+        if let Some(file) = c["file"].as_str() {
+            changelog.file = file.to_string();
+        }
+        changelog.order = strings(&c["order"]).unwrap_or_default();
+        changelog.hidden = strings(&c["hidden"]).unwrap_or_default();
+        changelog.mission_url = c["missionUrl"].as_str().map(str::to_string);
+
+        let b = &v[BUMP_KEY];
+        let mut bump = BumpConfig::default();
+        if let Some(prefix) = b["tagPrefix"].as_str() {
+            bump.tag_prefix = prefix.to_string();
+        }
+        if let Some(types) = b["types"].as_object() {
+            bump.levels = types
+                .iter()
+                .map(|(value, level)| {
+                    let level = level.as_str().and_then(Level::from_name);
+                    let level = level.ok_or_else(|| format!("custom.json: `bump.types.{}` must be \"major\", \"minor\" or \"patch\"", value))?;
+                    Ok((value.clone(), level))
+                })
+                .collect::<Result<_, String>>()?;
+        }
+        bump.files = strings(&b["files"]).unwrap_or_default();
+        if let Some(commit) = b["commit"].as_object() {
+            bump.commit = commit
+                .iter()
+                .filter_map(|(step, answer)| Some((step.clone(), answer.as_str()?.to_string())))
+                .collect();
+        }
+
+        let select_default = match v["history"]["selectDefault"].as_str() {
+            Some(name) => SelectDefault::from_name(name)
+                .ok_or("custom.json: `history.selectDefault` must be \"recent\", \"frequent\" or \"none\"")?,
+            None => SelectDefault::default(),
+        };
+
+        let mut layout = LayoutConfig::default();
+        if let Some(min_width) = v["layout"]["minWidth"].as_u64() {
+            layout.min_width = u16::try_from(min_width).unwrap_or(u16::MAX);
+        }
+        layout.narrow = match v["layout"]["narrow"].as_str() {
+            Some("stack") | None => NarrowLayout::Stack,
+            Some("hide") => NarrowLayout::Hide,
+            Some(_) => return Err("custom.json: `layout.narrow` must be \"stack\" or \"hide\"".into()),
+        };
+
+        let scope_rules = v["scopeRules"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|rule| match (rule["pattern"].as_str(), rule["scope"].as_str()) {
+                (Some(pattern), Some(scope)) => Ok(ScopeRule { pattern: pattern.to_string(), scope: scope.to_string() }),
+                _ => Err("custom.json: every `scopeRules` entry needs a `pattern` and a `scope`"),
+            })
+            .collect::<Result<_, _>>()?;
+
+        let tracker = match &v["tracker"] {
+            Value::Null => None,
+            t => Some(TrackerConfig::from_value(t).ok_or("custom.json: `tracker.url` must be a string")?),
+        };
+
+        let theme = match &v["theme"] {
+            Value::Null => Theme::default(),
+            t => Theme::from_value(t).map_err(|err| format!("custom.json: {}", err))?,
+        };
+
+        let keys = match &v["keys"] {
+            Value::Null => Keymap::default(),
+            k => Keymap::from_value(k).map_err(|err| format!("custom.json: {}", err))?,
+        };
+
+        Ok(Config {
+            command_map,
+            select_map,
+            flags,
+            log,
+            rules,
+            changelog,
+            bump,
+            signoff: v["signoff"].as_bool().unwrap_or(false),
+            gpg_sign: v["gpgSign"].as_bool().unwrap_or(false),
+            select_default,
+            scope_rules,
+            locale,
+            theme,
+            emoji,
+            command_sources,
+            descriptions,
+            tracker,
+            keys,
+            mouse: v["mouse"].as_bool().unwrap_or(true),
+            layout,
+        })
+    }
+
+    /// `(name, value)` of each `{"name", "value"}` object, `name` may be given per locale.
+    ///
+    /// A choice with an `emoji` (`:sparkles:` or `✨`) shows it before its name;
+    /// without a `value` the emoji is the value, otherwise it is recorded in
+    /// `emoji` and written before the value. A `description`, which may also be
+    /// given per locale, is recorded in `descriptions`
+    fn choices(
+        choices: &[Value],
+        locale: Locale,
+        emoji_format: EmojiFormat,
+        emoji: &mut HashMap<String, String>,
+        descriptions: &mut HashMap<String, String>,
+    ) -> Vec<(String, String)> {
+        choices
+            .iter()
+            .map(|o| {
+                let name = locale.pick(&o["name"]).unwrap_or_default();
+                let value = o["value"].as_str().unwrap_or_default().to_string();
+                let description = locale.pick(&o["description"]).filter(|d| !d.trim().is_empty());
+                let choice = match o["emoji"].as_str() {
+                    Some(e) if value.is_empty() => (format!("{} {}", gitmoji::unicode(e), name), gitmoji::render(e, emoji_format)),
+                    Some(e) => {
+                        emoji.insert(value.clone(), gitmoji::render(e, emoji_format));
+                        (format!("{} {}", gitmoji::unicode(e), name), value)
+                    }
+                    None => (name, value),
+                };
+                if let Some(description) = description {
+                    descriptions.insert(choice.1.clone(), description);
+                }
+                choice
+            })
+            .collect()
+    }
+
+    /// Run the `source` commands of `steps` side by side and merge their output after
+    /// the static choices; a failed command shows up as an error entry in its list
+    pub fn load_sources(&mut self, steps: &[String]) {
+        let sources: Vec<(String, CommandSource)> =
+            steps.iter().filter_map(|step| self.command_sources.remove_entry(step)).collect();
+        let loaded: Vec<(String, Vec<(String, String)>)> = std::thread::scope(|scope| {
+            let runs: Vec<_> = sources.iter().map(|(key, source)| (key, scope.spawn(|| source.choices()))).collect();
+            runs.into_iter().map(|(key, run)| (key.clone(), run.join().unwrap_or_default())).collect()
+        });
+        for (key, choices) in loaded {
+            merge(self.select_map.entry(key).or_default(), choices);
+        }
+    }
+
+    /// Step type a command line flag such as `--change-type` or `-t` answers
+    pub fn step_for_flag(&self, flag: &str) -> Option<&str> {
+        self.flags.iter().find(|(f, _)| f == flag).map(|(_, step)| step.as_str())
+    }
+
+    /// Options always passed to `git commit`
+    pub fn commit_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if self.signoff {
+            args.push("--signoff".to_string());
+        }
+        if self.gpg_sign {
+            args.push("-S".to_string());
+        }
+        args
+    }
+
+    pub fn template(&self) -> Template {
+        Template::new(self.command_map.iter().map(|(_, key)| key.clone()).collect()).with_emoji(self.emoji.clone())
+    }
+
+    /// Every value of every select list, used to highlight them in the log panel
+    pub fn known_values(&self) -> Vec<&str> {
+        self.select_map.values().flatten().map(|(_, value)| value.as_str()).collect()
+    }
+}
@@ -0,0 +1,279 @@
+use std::{error::Error, fmt, ops::Range, str::FromStr};
+
+use crate::template::is_trailer_block;
+
+/// A commit message following the Conventional Commits grammar, optionally
+/// prefixed by a mission (ticket) ID:
+///
+/// ```text
+/// [<mission-id> ]<type>[(<scope>[,<scope>...])][!]: <subject>
+///
+/// [body]
+///
+/// [footer(s)]
+/// ```
+///
+/// Parse it with [`str::parse`], render it back with [`ToString`]/[`fmt::Display`].
+/// Rendering a parsed message gives back the original text as long as it was
+/// written in canonical form (scopes separated by `,` without spaces, single
+/// blank lines between paragraphs).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConventionalCommit {
+    pub mission_id: Option<String>,
+    pub kind: String,
+    pub scopes: Vec<String>,
+    /// Whether the header carries the `!` marker, see [`ConventionalCommit::is_breaking`]
+    pub breaking: bool,
+    pub subject: String,
+    pub body: Option<String>,
+    pub footers: Vec<Footer>,
+}
+
+/// A `Token: value` or `Token #value` trailer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footer {
+    pub token: String,
+    pub separator: FooterSeparator,
+    /// Value of the trailer, continuation lines joined with `\n`
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FooterSeparator {
+    /// `Token: value`
+    Colon,
+    /// `Token #value`
+    Hash,
+}
+
+impl ConventionalCommit {
+    /// Breaking either through the `!` marker or a `BREAKING CHANGE` footer
+    pub fn is_breaking(&self) -> bool {
+        self.breaking || self.footers.iter().any(Footer::is_breaking_change)
+    }
+
+    /// Values of every footer with the given token, e.g. `Refs`
+    pub fn footer_values<'a>(&'a self, token: &'a str) -> impl Iterator<Item = &'a str> {
+        self.footers.iter().filter(move |f| f.token == token).map(|f| f.value.as_str())
+    }
+
+    /// The first line of the rendered message
+    pub fn header(&self) -> String {
+        let mut header = String::new();
+        if let Some(id) = &self.mission_id {
+            header.push_str(id);
+            header.push(' ');
+        }
+        header.push_str(&self.kind);
+        if !self.scopes.is_empty() {
+            header.push('(');
+            header.push_str(&self.scopes.join(","));
+            header.push(')');
+        }
+        if self.breaking {
+            header.push('!');
+        }
+        header.push_str(": ");
+        header.push_str(&self.subject);
+        header
+    }
+}
+
+impl Footer {
+    pub fn is_breaking_change(&self) -> bool {
+        self.token == "BREAKING CHANGE" || self.token == "BREAKING-CHANGE"
+    }
+
+    fn parse_line(line: &str) -> Option<Footer> {
+        let (end, separator, skip) = match (line.find(": "), line.find(" #")) {
+            (Some(colon), Some(hash)) if hash < colon => (hash, FooterSeparator::Hash, 2),
+            (Some(colon), _) => (colon, FooterSeparator::Colon, 2),
+            (None, Some(hash)) => (hash, FooterSeparator::Hash, 2),
+            (None, None) => return None,
+        };
+        if !is_trailer_block(line) {
+            return None;
+        }
+        Some(Footer { token: line[..end].to_string(), separator, value: line[end + skip..].to_string() })
+    }
+}
+
+impl fmt::Display for Footer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.separator {
+            FooterSeparator::Colon => write!(f, "{}: {}", self.token, self.value),
+            FooterSeparator::Hash => write!(f, "{} #{}", self.token, self.value),
+        }
+    }
+}
+
+impl fmt::Display for ConventionalCommit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.header())?;
+        if let Some(body) = &self.body {
+            write!(f, "\n\n{}", body)?;
+        }
+        if !self.footers.is_empty() {
+            f.write_str("\n")?;
+            for footer in &self.footers {
+                write!(f, "\n{}", footer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a message is not a conventional commit, with the byte range of the
+/// offending part of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    EmptyMessage,
+    MissingType,
+    InvalidType,
+    UnclosedScope,
+    EmptyScope,
+    /// The type/scope is not followed by `: `
+    MissingSeparator,
+    EmptySubject,
+    /// The line after the header is not blank
+    MissingBlankLine,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self.kind {
+            ParseErrorKind::EmptyMessage => "empty commit message",
+            ParseErrorKind::MissingType => "missing commit type",
+            ParseErrorKind::InvalidType => "commit type may only contain letters, digits, `-` and `_`",
+            ParseErrorKind::UnclosedScope => "scope is missing its closing `)`",
+            ParseErrorKind::EmptyScope => "empty scope",
+            ParseErrorKind::MissingSeparator => "expected `: ` after the type and scope",
+            ParseErrorKind::EmptySubject => "missing subject",
+            ParseErrorKind::MissingBlankLine => "the header must be followed by a blank line",
+        };
+        write!(f, "{} at {}..{}", message, self.span.start, self.span.end)
+    }
+}
+
+impl Error for ParseError {}
+
+fn error<T>(kind: ParseErrorKind, span: Range<usize>) -> Result<T, ParseError> {
+    Err(ParseError { kind, span })
+}
+
+/// `type[(scope, ...)][!]:` of the header starting at `type_start`: the type,
+/// the scopes, whether it is breaking and where the `:` is
+fn parse_prefix(header: &str, type_start: usize) -> Result<(String, Vec<String>, bool, usize), ParseError> {
+    let type_end = header[type_start..].find(['(', '!', ':', ' ']).map_or(header.len(), |i| type_start + i);
+    let kind = &header[type_start..type_end];
+    if kind.is_empty() {
+        return error(ParseErrorKind::MissingType, type_start..type_end.max(type_start + 1).min(header.len()));
+    }
+    if !kind.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return error(ParseErrorKind::InvalidType, type_start..type_end);
+    }
+
+    let mut pos = type_end;
+    let mut scopes = vec![];
+    if header[pos..].starts_with('(') {
+        let close = match header[pos..].find(')') {
+            Some(i) => pos + i,
+            None => return error(ParseErrorKind::UnclosedScope, pos..header.len()),
+        };
+        let mut scope_start = pos + 1;
+        for scope in header[pos + 1..close].split(',') {
+            let trimmed = scope.trim();
+            if trimmed.is_empty() {
+                return error(ParseErrorKind::EmptyScope, scope_start..scope_start + scope.len());
+            }
+            scopes.push(trimmed.to_string());
+            scope_start += scope.len() + 1;
+        }
+        pos = close + 1;
+    }
+    let breaking = header[pos..].starts_with('!');
+    if breaking {
+        pos += 1;
+    }
+    if !header[pos..].starts_with(": ") && header[pos..] != *":" {
+        return error(ParseErrorKind::MissingSeparator, pos..(pos + 2).min(header.len()).max(pos));
+    }
+    Ok((kind.to_string(), scopes, breaking, pos))
+}
+
+impl FromStr for ConventionalCommit {
+    type Err = ParseError;
+
+    fn from_str(message: &str) -> Result<Self, Self::Err> {
+        let header_end = message.find('\n').unwrap_or(message.len());
+        let header = &message[..header_end];
+        if header.trim().is_empty() {
+            return error(ParseErrorKind::EmptyMessage, 0..header_end);
+        }
+
+        // 本身不是 type[(scope)][!]: 开头, 而第一个词之后的部分是时, 第一个词为任务ID
+        let (mission_id, (kind, scopes, breaking, pos)) = match parse_prefix(header, 0) {
+            Ok(prefix) => (None, prefix),
+            Err(err) => {
+                let space = header.find(' ').filter(|&space| space > 0).ok_or_else(|| err.clone())?;
+                let word = &header[..space];
+                if word.ends_with(':') || word.contains(['(', '!']) {
+                    return Err(err);
+                }
+                (Some(word.to_string()), parse_prefix(header, space + 1).map_err(|_| err)?)
+            }
+        };
+        let subject = header[(pos + 2).min(header.len())..].trim();
+        if subject.is_empty() {
+            return error(ParseErrorKind::EmptySubject, header.len()..header.len());
+        }
+
+        let mut commit = ConventionalCommit {
+            mission_id,
+            kind,
+            scopes,
+            breaking,
+            subject: subject.to_string(),
+            body: None,
+            footers: vec![],
+        };
+        if header_end == message.len() {
+            return Ok(commit);
+        }
+
+        let rest_start = header_end + 1;
+        let rest = &message[rest_start..];
+        let second_line = rest.lines().next().unwrap_or_default();
+        if !second_line.trim().is_empty() {
+            return error(ParseErrorKind::MissingBlankLine, rest_start..rest_start + second_line.len());
+        }
+
+        let mut paragraphs: Vec<&str> = rest.split("\n\n").map(|p| p.trim_matches('\n')).filter(|p| !p.trim().is_empty()).collect();
+        if let Some(last) = paragraphs.last() {
+            if last.lines().next().and_then(Footer::parse_line).is_some() {
+                for line in last.lines() {
+                    match Footer::parse_line(line) {
+                        Some(footer) => commit.footers.push(footer),
+                        // 续行属于上一个footer
+                        None => {
+                            let footer = commit.footers.last_mut().unwrap();
+                            footer.value.push('\n');
+                            footer.value.push_str(line);
+                        }
+                    }
+                }
+                paragraphs.pop();
+            }
+        }
+        if !paragraphs.is_empty() {
+            commit.body = Some(paragraphs.join("\n\n"));
+        }
+        Ok(commit)
+    }
+}
@@ -0,0 +1,88 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+const VARIATION_SELECTOR: char = '\u{fe0f}';
+
+/// Make `text` take as many columns in the terminal as the TUI thinks it does.
+///
+/// Emoji such as `♻️` are a narrow character plus U+FE0F, which terminals draw
+/// two columns wide while older `unicode-width` releases count one. They are
+/// drawn in their narrow text form followed by a space instead, so the columns
+/// line up whichever release measures them.
+pub fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for grapheme in text.graphemes(true) {
+        let base: String = grapheme.chars().filter(|&c| c != VARIATION_SELECTOR).collect();
+        if grapheme.contains(VARIATION_SELECTOR) && base.width() < 2 {
+            out.push_str(&base);
+            out.push(' ');
+        } else {
+            out.push_str(grapheme);
+        }
+    }
+    out
+}
+
+/// Columns `text` takes once [`normalize`]d, e.g. to place the cursor after it
+pub fn width(text: &str) -> usize {
+    normalize(text).width()
+}
+
+/// `text` cut at a grapheme boundary to fit in `max` columns, ending in `…` when cut
+pub fn ellipsize(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let columns = width(grapheme);
+        if used + columns + 1 > max {
+            break;
+        }
+        out.push_str(grapheme);
+        used += columns;
+    }
+    if max > 0 {
+        out.push('…');
+    }
+    out
+}
+
+/// `text` broken into lines of at most `width` columns at spaces; a word wider
+/// than a line, or Chinese text without spaces, is broken between graphemes
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut used = 0;
+        for word in paragraph.split_whitespace() {
+            let columns = self::width(word);
+            let gap = usize::from(!line.is_empty());
+            if used + gap + columns <= width {
+                if gap == 1 {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                used += gap + columns;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                used = 0;
+            }
+            for grapheme in word.graphemes(true) {
+                let columns = self::width(grapheme);
+                if used + columns > width && !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                    used = 0;
+                }
+                line.push_str(grapheme);
+                used += columns;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,58 @@
+use std::{collections::HashMap, error::Error, fs, path::PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::hook::git_path;
+
+const DRAFT_FILE: &str = "rcz/draft.json";
+
+/// Answers kept after an aborted or failed commit, keyed by step type
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Draft {
+    pub answers: HashMap<String, String>,
+}
+
+impl Draft {
+    /// Pair the answers given so far with their steps
+    pub fn new(steps: &[String], answers: &[String]) -> Draft {
+        Draft { answers: steps.iter().cloned().zip(answers.iter().cloned()).collect() }
+    }
+
+    pub fn path() -> Result<PathBuf, Box<dyn Error>> {
+        git_path(DRAFT_FILE)
+    }
+
+    /// The saved draft, `None` when there is none (or it cannot be read)
+    pub fn load() -> Option<Draft> {
+        let content = fs::read_to_string(Draft::path().ok()?).ok()?;
+        let v: Value = serde_json::from_str(&content).ok()?;
+        let answers = v["answers"]
+            .as_object()?
+            .iter()
+            .filter_map(|(step, answer)| Some((step.clone(), answer.as_str()?.to_string())))
+            .collect();
+        Some(Draft { answers })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let path = Draft::path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let answers: Map<String, Value> = self.answers.iter().map(|(k, v)| (k.clone(), Value::from(v.as_str()))).collect();
+        fs::write(path, serde_json::json!({ "answers": answers }).to_string())?;
+        Ok(())
+    }
+
+    /// Remove the saved draft, a missing draft is not an error
+    pub fn clear() {
+        if let Ok(path) = Draft::path() {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Answers of the leading steps the draft covers, in step order
+    pub fn answers_for(&self, steps: &[String]) -> Vec<String> {
+        steps.iter().map_while(|step| self.answers.get(step).cloned()).collect()
+    }
+}
@@ -0,0 +1,131 @@
+/// How an emoji is written into the commit message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmojiFormat {
+    /// `:sparkles:`
+    #[default]
+    Code,
+    /// `✨`
+    Unicode,
+}
+
+impl EmojiFormat {
+    pub fn from_name(name: &str) -> Option<EmojiFormat> {
+        match name {
+            "code" => Some(EmojiFormat::Code),
+            "unicode" => Some(EmojiFormat::Unicode),
+            _ => None,
+        }
+    }
+}
+
+/// (code, unicode, description) of the bundled gitmoji list, see https://gitmoji.dev
+pub const GITMOJIS: &[(&str, &str, &str)] = &[
+    (":art:", "🎨", "Improve structure / format of the code"),
+    (":zap:", "⚡️", "Improve performance"),
+    (":fire:", "🔥", "Remove code or files"),
+    (":bug:", "🐛", "Fix a bug"),
+    (":ambulance:", "🚑️", "Critical hotfix"),
+    (":sparkles:", "✨", "Introduce new features"),
+    (":memo:", "📝", "Add or update documentation"),
+    (":rocket:", "🚀", "Deploy stuff"),
+    (":lipstick:", "💄", "Add or update the UI and style files"),
+    (":tada:", "🎉", "Begin a project"),
+    (":white_check_mark:", "✅", "Add, update, or pass tests"),
+    (":lock:", "🔒️", "Fix security issues"),
+    (":closed_lock_with_key:", "🔐", "Add or update secrets"),
+    (":bookmark:", "🔖", "Release / Version tags"),
+    (":rotating_light:", "🚨", "Fix compiler / linter warnings"),
+    (":construction:", "🚧", "Work in progress"),
+    (":green_heart:", "💚", "Fix CI Build"),
+    (":arrow_down:", "⬇️", "Downgrade dependencies"),
+    (":arrow_up:", "⬆️", "Upgrade dependencies"),
+    (":pushpin:", "📌", "Pin dependencies to specific versions"),
+    (":construction_worker:", "👷", "Add or update CI build system"),
+    (":chart_with_upwards_trend:", "📈", "Add or update analytics or track code"),
+    (":recycle:", "♻️", "Refactor code"),
+    (":heavy_plus_sign:", "➕", "Add a dependency"),
+    (":heavy_minus_sign:", "➖", "Remove a dependency"),
+    (":wrench:", "🔧", "Add or update configuration files"),
+    (":hammer:", "🔨", "Add or update development scripts"),
+    (":globe_with_meridians:", "🌐", "Internationalization and localization"),
+    (":pencil2:", "✏️", "Fix typos"),
+    (":poop:", "💩", "Write bad code that needs to be improved"),
+    (":rewind:", "⏪️", "Revert changes"),
+    (":twisted_rightwards_arrows:", "🔀", "Merge branches"),
+    (":package:", "📦️", "Add or update compiled files or packages"),
+    (":alien:", "👽️", "Update code due to external API changes"),
+    (":truck:", "🚚", "Move or rename resources"),
+    (":page_facing_up:", "📄", "Add or update license"),
+    (":boom:", "💥", "Introduce breaking changes"),
+    (":bento:", "🍱", "Add or update assets"),
+    (":wheelchair:", "♿️", "Improve accessibility"),
+    (":bulb:", "💡", "Add or update comments in source code"),
+    (":speech_balloon:", "💬", "Add or update text and literals"),
+    (":card_file_box:", "🗃️", "Perform database related changes"),
+    (":loud_sound:", "🔊", "Add or update logs"),
+    (":mute:", "🔇", "Remove logs"),
+    (":busts_in_silhouette:", "👥", "Add or update contributor(s)"),
+    (":children_crossing:", "🚸", "Improve user experience / usability"),
+    (":building_construction:", "🏗️", "Make architectural changes"),
+    (":iphone:", "📱", "Work on responsive design"),
+    (":clown_face:", "🤡", "Mock things"),
+    (":see_no_evil:", "🙈", "Add or update a .gitignore file"),
+    (":camera_flash:", "📸", "Add or update snapshots"),
+    (":label:", "🏷️", "Add or update types"),
+    (":seedling:", "🌱", "Add or update seed files"),
+    (":triangular_flag_on_post:", "🚩", "Add, update, or remove feature flags"),
+    (":goal_net:", "🥅", "Catch errors"),
+    (":dizzy:", "💫", "Add or update animations and transitions"),
+    (":wastebasket:", "🗑️", "Deprecate code that needs to be cleaned up"),
+    (":passport_control:", "🛂", "Work on code related to authorization, roles and permissions"),
+    (":adhesive_bandage:", "🩹", "Simple fix for a non-critical issue"),
+    (":monocle_face:", "🧐", "Data exploration/inspection"),
+    (":coffin:", "⚰️", "Remove dead code"),
+    (":test_tube:", "🧪", "Add a failing test"),
+    (":necktie:", "👔", "Add or update business logic"),
+    (":stethoscope:", "🩺", "Add or update healthcheck"),
+    (":bricks:", "🧱", "Infrastructure related changes"),
+    (":technologist:", "🧑‍💻", "Improve developer experience"),
+    (":thread:", "🧵", "Add or update code related to multithreading or concurrency"),
+    (":safety_vest:", "🦺", "Add or update code related to validation"),
+];
+
+/// `(code, unicode)` of a bundled gitmoji given in either form;
+/// the variation selector U+FE0F is optional in the unicode form
+pub fn lookup(emoji: &str) -> Option<(&'static str, &'static str)> {
+    let bare = |s: &str| s.replace('\u{fe0f}', "");
+    GITMOJIS
+        .iter()
+        .find(|(code, unicode, _)| *code == emoji || bare(unicode) == bare(emoji))
+        .map(|(code, unicode, _)| (*code, *unicode))
+}
+
+/// An emoji in the configured form; unknown emoji are kept as written
+pub fn render(emoji: &str, format: EmojiFormat) -> String {
+    match (lookup(emoji), format) {
+        (Some((code, _)), EmojiFormat::Code) => code.to_string(),
+        (Some((_, unicode)), EmojiFormat::Unicode) => unicode.to_string(),
+        (None, _) => emoji.to_string(),
+    }
+}
+
+/// The unicode form for display, `:code:`s that are not bundled stay as they are
+pub fn unicode(emoji: &str) -> String {
+    render(emoji, EmojiFormat::Unicode)
+}
+
+/// Whether `word` is an emoji in either form
+pub fn is_emoji(word: &str) -> bool {
+    lookup(word).is_some() || (word.len() > 2 && word.starts_with(':') && word.ends_with(':'))
+}
+
+/// `(name, value)` choices of the bundled list, the value being the emoji alone
+pub fn choices(emoji_format: EmojiFormat) -> Vec<(String, String)> {
+    GITMOJIS
+        .iter()
+        .map(|(code, unicode, description)| {
+            let value = render(code, emoji_format);
+            (format!("{} {} {}", unicode, code, description), value)
+        })
+        .collect()
+}
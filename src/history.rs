@@ -0,0 +1,148 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    error::Error,
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::{json, Map, Value};
+
+use crate::hook::git_path;
+
+const HISTORY_FILE: &str = "rcz/history.json";
+/// Values kept per step
+const LIMIT: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub value: String,
+    pub count: u64,
+    /// Seconds since the epoch
+    pub last_used: u64,
+}
+
+/// Which choice a select list starts on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectDefault {
+    #[default]
+    Recent,
+    Frequent,
+    None,
+}
+
+impl SelectDefault {
+    pub fn from_name(name: &str) -> Option<SelectDefault> {
+        match name {
+            "recent" => Some(SelectDefault::Recent),
+            "frequent" => Some(SelectDefault::Frequent),
+            "none" => Some(SelectDefault::None),
+            _ => None,
+        }
+    }
+}
+
+/// Answers given in earlier commits of this repository, per step type
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    steps: HashMap<String, Vec<Entry>>,
+}
+
+impl History {
+    pub fn path() -> Result<PathBuf, Box<dyn Error>> {
+        git_path(HISTORY_FILE)
+    }
+
+    /// The saved history, empty when there is none yet
+    pub fn load() -> History {
+        let content = History::path().ok().and_then(|path| fs::read_to_string(path).ok());
+        content.and_then(|content| serde_json::from_str(&content).ok()).map(|v| History::from_value(&v)).unwrap_or_default()
+    }
+
+    pub fn from_value(v: &Value) -> History {
+        let steps = v
+            .as_object()
+            .map(|steps| steps.iter().map(|(step, entries)| (step.clone(), History::entries(entries))).collect())
+            .unwrap_or_default();
+        History { steps }
+    }
+
+    fn entries(v: &Value) -> Vec<Entry> {
+        let entries = v.as_array().map(Vec::as_slice).unwrap_or_default();
+        entries
+            .iter()
+            .filter_map(|e| {
+                Some(Entry {
+                    value: e["value"].as_str()?.to_string(),
+                    count: e["count"].as_u64().unwrap_or(1),
+                    last_used: e["lastUsed"].as_u64().unwrap_or(0),
+                })
+            })
+            .collect()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let path = History::path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let steps: Map<String, Value> = self
+            .steps
+            .iter()
+            .map(|(step, entries)| {
+                let entries = entries
+                    .iter()
+                    .map(|e| json!({"value": e.value, "count": e.count, "lastUsed": e.last_used}))
+                    .collect::<Vec<_>>();
+                (step.clone(), Value::from(entries))
+            })
+            .collect();
+        fs::write(path, Value::from(steps).to_string())?;
+        Ok(())
+    }
+
+    /// Remember `value` as the latest answer of `step`
+    pub fn record(&mut self, step: &str, value: &str) {
+        if value.trim().is_empty() {
+            return;
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let entries = self.steps.entry(step.to_string()).or_default();
+        let count = match entries.iter().position(|e| e.value == value) {
+            Some(i) => entries.remove(i).count + 1,
+            None => 1,
+        };
+        entries.insert(0, Entry { value: value.to_string(), count, last_used: now });
+        entries.truncate(LIMIT);
+    }
+
+    /// Values of `step`, most recently used first
+    pub fn recent(&self, step: &str) -> Vec<&str> {
+        let mut entries: Vec<&Entry> = self.steps.get(step).map(|e| e.iter().collect()).unwrap_or_default();
+        entries.sort_by_key(|e| Reverse(e.last_used));
+        entries.into_iter().map(|e| e.value.as_str()).collect()
+    }
+
+    /// The value of `step` used most often, the most recent one on a tie
+    pub fn most_frequent(&self, step: &str) -> Option<&str> {
+        let entries = self.steps.get(step)?;
+        entries
+            .iter()
+            .max_by(|a, b| a.count.cmp(&b.count).then(a.last_used.cmp(&b.last_used)))
+            .map(|e| e.value.as_str())
+    }
+
+    pub fn default_choice(&self, step: &str, default: SelectDefault) -> Option<&str> {
+        match default {
+            SelectDefault::Recent => self.recent(step).first().copied(),
+            SelectDefault::Frequent => self.most_frequent(step),
+            SelectDefault::None => None,
+        }
+    }
+
+    /// Most recent value of `step` that extends `prefix`
+    pub fn complete(&self, step: &str, prefix: &str) -> Option<&str> {
+        self.recent(step).into_iter().find(|value| value.starts_with(prefix) && value.len() > prefix.len())
+    }
+}
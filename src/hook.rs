@@ -0,0 +1,52 @@
+use std::{error::Error, fs, path::{Path, PathBuf}, process::Command};
+
+pub const HOOK_NAME: &str = "prepare-commit-msg";
+const MARKER: &str = "# installed by rcz";
+
+/// Commit message sources (second hook argument) for which the message is
+/// already decided, so the hook leaves it alone
+const SKIPPED_SOURCES: [&str; 3] = ["merge", "squash", "message"];
+
+/// Whether the hook should leave the message file untouched for this source
+pub fn should_skip(source: Option<&str>) -> bool {
+    source.is_some_and(|source| SKIPPED_SOURCES.contains(&source))
+}
+
+/// Path of a file inside the git directory, e.g. `hooks/prepare-commit-msg`
+pub fn git_path(path: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let output = Command::new("git").args(["rev-parse", "--git-path", path]).output()?;
+    if !output.status.success() {
+        return Err("not a git repository".into());
+    }
+    Ok(PathBuf::from(String::from_utf8(output.stdout)?.trim()))
+}
+
+/// Shell script git runs as the hook; it hands the terminal to `exe`.
+/// Without a terminal (IDE, GUI, CI) or without `exe` the commit goes ahead untouched
+pub fn script(exe: &Path) -> String {
+    format!(
+        "#!/bin/sh\n{marker}\n[ -x \"{exe}\" ] || exit 0\n(exec < /dev/tty) 2>/dev/null || exit 0\nexec < /dev/tty\nexec \"{exe}\" hook run \"$@\"\n",
+        marker = MARKER,
+        exe = exe.display()
+    )
+}
+
+/// Write the `prepare-commit-msg` hook, refusing to replace a hook rcz did not install
+pub fn install(exe: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let path = git_path(&format!("hooks/{}", HOOK_NAME))?;
+    if let Ok(existing) = fs::read_to_string(&path) {
+        if !existing.contains(MARKER) {
+            return Err(format!("{} already exists and was not installed by rcz", path.display()).into());
+        }
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, script(exe))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(path)
+}
@@ -0,0 +1,214 @@
+use serde_json::Value;
+
+/// Language of the UI strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    ZhCn,
+    #[default]
+    En,
+}
+
+/// Every string the UI shows; `{...}` marks a value filled in by [`fill`],
+/// in key hints it marks a key drawn in bold
+#[derive(Debug)]
+pub struct Catalog {
+    pub mission_id_prompt: &'static str,
+    pub change_type_prompt: &'static str,
+    pub scope_prompt: &'static str,
+    pub subject_prompt: &'static str,
+    pub input_title: &'static str,
+    pub messages_title: &'static str,
+    pub select_title: &'static str,
+    pub confirm_title: &'static str,
+    pub confirm_hint: &'static str,
+    pub commit_message: &'static str,
+    pub draft_title: &'static str,
+    pub draft_hint: &'static str,
+    /// `{scope}` is one of the two names below, `{key}` the key that toggles it
+    pub log_title: &'static str,
+    pub log_scope_all: &'static str,
+    pub log_scope_branch: &'static str,
+    /// Branch scope without an upstream, which shows all commits instead
+    pub log_scope_no_upstream: &'static str,
+    pub ticket_title: &'static str,
+    pub ticket_not_found: &'static str,
+    pub ticket_not_checked: &'static str,
+    /// Notice under the prompt while the tracker is asked about the MissionId
+    pub mission_checking: &'static str,
+    /// Notice under the prompt when the MissionId does not exist
+    pub mission_not_found: &'static str,
+    /// Notice under the prompt when the tracker could not be reached
+    pub mission_not_checked: &'static str,
+    pub help_title: &'static str,
+    pub help_close: &'static str,
+    /// Shown after the list title while filtering, `{filter}` is the typed text
+    pub filter_title: &'static str,
+    /// Block under the select list with the description of the highlighted choice
+    pub details_title: &'static str,
+    pub action_next: &'static str,
+    pub action_previous: &'static str,
+    pub action_history_newer: &'static str,
+    pub action_history_older: &'static str,
+    pub action_submit: &'static str,
+    pub action_commit: &'static str,
+    pub action_restore: &'static str,
+    pub action_discard: &'static str,
+    pub action_back: &'static str,
+    pub action_abort: &'static str,
+    pub action_toggle_log: &'static str,
+    pub action_toggle_preview: &'static str,
+    pub action_filter: &'static str,
+    pub action_scroll_preview: &'static str,
+    pub action_complete: &'static str,
+    pub action_delete: &'static str,
+    pub action_help: &'static str,
+}
+
+pub const ZH_CN: Catalog = Catalog {
+    mission_id_prompt: "请输入任务ID:",
+    change_type_prompt: "请选择变更类型:",
+    scope_prompt: "请输入变更范围:",
+    subject_prompt: "请输入变更概述:",
+    input_title: "输入",
+    messages_title: "已填写",
+    select_title: "请选择",
+    confirm_title: "确认",
+    confirm_hint: "按 {Esc} 退出, 按 {Enter} 提交",
+    commit_message: "提交信息: ",
+    draft_title: "草稿",
+    draft_hint: "恢复上次的草稿? 按 {y} 恢复, 按 {n} 重新开始",
+    log_title: "git log ({scope}, {key} 切换)",
+    log_scope_all: "全部",
+    log_scope_branch: "当前分支",
+    log_scope_no_upstream: "当前分支没有上游, 显示全部",
+    ticket_title: "任务 {id}",
+    ticket_not_found: "任务不存在",
+    ticket_not_checked: "未能验证: {reason}",
+    mission_checking: "正在查询任务 {id}...",
+    mission_not_found: "任务 {id} 不存在, 再次提交仍然使用",
+    mission_not_checked: "无法验证任务 {id}: {reason}",
+    help_title: "快捷键",
+    help_close: "按任意键关闭",
+    filter_title: "筛选: {filter}",
+    details_title: "说明",
+    action_next: "下一项",
+    action_previous: "上一项",
+    action_history_newer: "较新的历史记录",
+    action_history_older: "较早的历史记录",
+    action_submit: "确定",
+    action_commit: "提交",
+    action_restore: "恢复草稿",
+    action_discard: "丢弃草稿",
+    action_back: "取消选择",
+    action_abort: "退出(保存草稿)",
+    action_toggle_log: "切换 git log 范围",
+    action_toggle_preview: "显示/隐藏 git log",
+    action_filter: "筛选",
+    action_scroll_preview: "滚动 git log",
+    action_complete: "用历史记录补全",
+    action_delete: "删除字符",
+    action_help: "帮助",
+};
+
+pub const EN: Catalog = Catalog {
+    mission_id_prompt: "Mission ID:",
+    change_type_prompt: "Select the type of change:",
+    scope_prompt: "Scope of the change:",
+    subject_prompt: "Short description of the change:",
+    input_title: "Input",
+    messages_title: "Messages",
+    select_title: "Selected",
+    confirm_title: "Confirm",
+    confirm_hint: "Press {Esc} to quit, {Enter} to commit the message",
+    commit_message: "commit message: ",
+    draft_title: "Draft",
+    draft_hint: "Restore previous draft? Press {y} to restore, {n} to start over",
+    log_title: "git log ({scope}, {key} to toggle)",
+    log_scope_all: "all",
+    log_scope_branch: "branch",
+    log_scope_no_upstream: "branch has no upstream, showing all",
+    ticket_title: "Ticket {id}",
+    ticket_not_found: "not found",
+    ticket_not_checked: "not checked: {reason}",
+    mission_checking: "checking {id}...",
+    mission_not_found: "{id} not found, submit again to use it anyway",
+    mission_not_checked: "could not check {id}: {reason}",
+    help_title: "Keys",
+    help_close: "Press any key to close",
+    filter_title: "filter: {filter}",
+    details_title: "Details",
+    action_next: "next",
+    action_previous: "previous",
+    action_history_newer: "newer history entry",
+    action_history_older: "older history entry",
+    action_submit: "confirm",
+    action_commit: "commit",
+    action_restore: "restore draft",
+    action_discard: "discard draft",
+    action_back: "unselect",
+    action_abort: "quit (keeps a draft)",
+    action_toggle_log: "toggle git log scope",
+    action_toggle_preview: "show/hide git log",
+    action_filter: "filter",
+    action_scroll_preview: "scroll git log",
+    action_complete: "complete from history",
+    action_delete: "delete character",
+    action_help: "help",
+};
+
+impl Locale {
+    /// `zh-CN`, `zh_CN.UTF-8`, `en`, `en_US` ...; any other Chinese or English tag falls back to these
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(['_', '-', '.']).next().unwrap_or_default().to_lowercase();
+        match language.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    /// From `LC_ALL`, `LC_MESSAGES` or `LANG`, in the order POSIX gives them precedence
+    pub fn detect() -> Locale {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|tag| Locale::from_tag(&tag))
+            .unwrap_or_default()
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::En => "en",
+        }
+    }
+
+    pub fn catalog(self) -> &'static Catalog {
+        match self {
+            Locale::ZhCn => &ZH_CN,
+            Locale::En => &EN,
+        }
+    }
+
+    /// A config string that may be given per locale: `"text"` or
+    /// `{"zh-CN": "...", "en": "..."}`; falls back to `en`, then to any variant
+    pub fn pick(self, v: &Value) -> Option<String> {
+        match v {
+            Value::String(text) => Some(text.clone()),
+            Value::Object(variants) => variants
+                .iter()
+                .find(|(tag, _)| Locale::from_tag(tag) == Some(self))
+                .or_else(|| variants.iter().find(|(tag, _)| Locale::from_tag(tag) == Some(Locale::En)))
+                .or_else(|| variants.iter().next())
+                .and_then(|(_, text)| text.as_str())
+                .map(str::to_string),
+            _ => None,
+        }
+    }
+}
+
+/// Replace each `{name}` with its value
+pub fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    values.iter().fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
@@ -0,0 +1,344 @@
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde_json::Value;
+
+use crate::i18n::Catalog;
+
+/// Built-in sets of bindings, `keys.preset` in the config
+pub const PRESETS: [&str; 3] = ["default", "vim", "emacs"];
+
+/// Screens with their own key bindings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Type,
+    Select,
+    Confirm,
+    /// Asking whether to restore the saved draft
+    Restore,
+}
+
+/// What a key does; the event loop only ever matches on these
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Next,
+    Previous,
+    Submit,
+    /// Unselect the list entry
+    Back,
+    Abort,
+    /// Switch the log panel between all branches and the current one
+    ToggleLog,
+    /// Show or hide the right pane
+    TogglePreview,
+    /// Narrow the select list down by typing
+    Filter,
+    /// Scroll the log panel
+    ScrollPreview,
+    /// Complete the input from the history
+    Complete,
+    DeleteChar,
+    /// Throw the draft away and start over
+    Discard,
+    Help,
+}
+
+/// Every action with its name in the `keys` config
+const ACTIONS: [(Action, &str); 13] = [
+    (Action::Next, "next"),
+    (Action::Previous, "previous"),
+    (Action::Submit, "submit"),
+    (Action::Back, "back"),
+    (Action::Abort, "abort"),
+    (Action::ToggleLog, "toggle"),
+    (Action::TogglePreview, "toggle-preview"),
+    (Action::Filter, "filter"),
+    (Action::ScrollPreview, "scroll-preview"),
+    (Action::Complete, "complete"),
+    (Action::DeleteChar, "delete"),
+    (Action::Discard, "discard"),
+    (Action::Help, "help"),
+];
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTIONS.iter().find(|(_, n)| *n == name).map(|(action, _)| *action)
+    }
+
+    pub fn name(self) -> &'static str {
+        ACTIONS.iter().find(|(a, _)| *a == self).map(|(_, name)| *name).unwrap_or_default()
+    }
+
+    /// Modes the action is bound in
+    fn modes(self) -> &'static [Mode] {
+        const ALL: &[Mode] = &[Mode::Type, Mode::Select, Mode::Confirm, Mode::Restore];
+        match self {
+            Action::Next | Action::Previous => &[Mode::Type, Mode::Select],
+            Action::Back | Action::Filter => &[Mode::Select],
+            Action::Complete => &[Mode::Type],
+            // 筛选时删除筛选文字
+            Action::DeleteChar => &[Mode::Type, Mode::Select],
+            Action::Discard => &[Mode::Restore],
+            Action::Submit
+            | Action::Abort
+            | Action::ToggleLog
+            | Action::TogglePreview
+            | Action::ScrollPreview
+            | Action::Help => ALL,
+        }
+    }
+
+    pub fn description(self, mode: Mode, catalog: &Catalog) -> &'static str {
+        match (self, mode) {
+            (Action::Next, Mode::Type) => catalog.action_history_newer,
+            (Action::Previous, Mode::Type) => catalog.action_history_older,
+            (Action::Next, _) => catalog.action_next,
+            (Action::Previous, _) => catalog.action_previous,
+            (Action::Submit, Mode::Confirm) => catalog.action_commit,
+            (Action::Submit, Mode::Restore) => catalog.action_restore,
+            (Action::Submit, _) => catalog.action_submit,
+            (Action::Back, _) => catalog.action_back,
+            (Action::Abort, _) => catalog.action_abort,
+            (Action::ToggleLog, _) => catalog.action_toggle_log,
+            (Action::TogglePreview, _) => catalog.action_toggle_preview,
+            (Action::Filter, _) => catalog.action_filter,
+            (Action::ScrollPreview, _) => catalog.action_scroll_preview,
+            (Action::Complete, _) => catalog.action_complete,
+            (Action::DeleteChar, _) => catalog.action_delete,
+            (Action::Discard, _) => catalog.action_discard,
+            (Action::Help, _) => catalog.action_help,
+        }
+    }
+}
+
+/// A key plus the modifiers held with it, e.g. `Ctrl+n`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub const fn new(code: KeyCode) -> KeyChord {
+        KeyChord { code, modifiers: KeyModifiers::NONE }
+    }
+
+    pub const fn ctrl(c: char) -> KeyChord {
+        KeyChord { code: KeyCode::Char(c), modifiers: KeyModifiers::CONTROL }
+    }
+
+    /// `ctrl+n`, `C-n`, `alt+x`, `M-x`, `enter`, `pagedown`, `f2`, `?` ...
+    pub fn parse(text: &str) -> Option<KeyChord> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = text;
+        loop {
+            let lower = key.to_lowercase();
+            if let Some(rest) = ["ctrl+", "c-"].iter().find_map(|p| lower.starts_with(p).then(|| &key[p.len()..])) {
+                modifiers |= KeyModifiers::CONTROL;
+                key = rest;
+            } else if let Some(rest) = ["alt+", "m-"].iter().find_map(|p| lower.starts_with(p).then(|| &key[p.len()..])) {
+                modifiers |= KeyModifiers::ALT;
+                key = rest;
+            } else {
+                break;
+            }
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok().filter(|n| (1..=12).contains(n))?),
+            },
+        };
+        Some(KeyChord { code, modifiers })
+    }
+
+    /// A key without modifiers that types a character
+    pub fn is_plain_char(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && self.modifiers.is_empty()
+    }
+
+    /// Shift is ignored for characters, it is already part of `?` or `G`
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let modifiers = match event.code {
+            KeyCode::Char(_) => event.modifiers - KeyModifiers::SHIFT,
+            _ => event.modifiers,
+        };
+        self.code == event.code && self.modifiers == modifiers
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Key bindings of every mode, in the order the help lists them
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Mode, KeyChord, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::preset("default").unwrap_or(Keymap { bindings: vec![] })
+    }
+}
+
+/// Keys of every action in a preset
+fn preset_keys(name: &str) -> Option<Vec<(Action, Vec<&'static str>)>> {
+    use Action::*;
+    let mut keys = vec![
+        (Submit, vec!["enter"]),
+        (Next, vec!["down"]),
+        (Previous, vec!["up"]),
+        (Back, vec!["left"]),
+        (Filter, vec!["/"]),
+        (ScrollPreview, vec!["pagedown"]),
+        (Complete, vec!["tab"]),
+        (DeleteChar, vec!["backspace"]),
+        (Discard, vec!["n"]),
+        (ToggleLog, vec!["f2"]),
+        (TogglePreview, vec!["f4"]),
+        (Help, vec!["f1", "?"]),
+        (Abort, vec!["esc"]),
+    ];
+    let extra: &[(Action, &[&str])] = match name {
+        "default" => &[],
+        "vim" => &[
+            (Next, &["j", "ctrl+n"]),
+            (Previous, &["k", "ctrl+p"]),
+            (Back, &["h"]),
+            (ScrollPreview, &["ctrl+d"]),
+            (Abort, &["ctrl+c"]),
+        ],
+        "emacs" => &[
+            (Submit, &["ctrl+j"]),
+            (Next, &["ctrl+n"]),
+            (Previous, &["ctrl+p"]),
+            (Back, &["ctrl+b"]),
+            (Filter, &["ctrl+s"]),
+            (ScrollPreview, &["ctrl+v"]),
+            (Complete, &["alt+/"]),
+            (DeleteChar, &["ctrl+h"]),
+            (Abort, &["ctrl+g"]),
+        ],
+        _ => return None,
+    };
+    for (action, chords) in extra {
+        if let Some((_, keys)) = keys.iter_mut().find(|(a, _)| a == action) {
+            keys.extend_from_slice(chords);
+        }
+    }
+    Some(keys)
+}
+
+impl Keymap {
+    pub fn preset(name: &str) -> Option<Keymap> {
+        Keymap::from_value(&serde_json::json!({ "preset": name })).ok()
+    }
+
+    /// Bind each action's keys in every mode it applies to; plain characters
+    /// are not bound in Type mode, where they are text
+    pub fn from_actions(actions: Vec<(Action, Vec<KeyChord>)>) -> Keymap {
+        // 草稿提示中的 y 总是恢复
+        let mut bindings = vec![(Mode::Restore, KeyChord::new(KeyCode::Char('y')), Action::Submit)];
+        for (action, chords) in actions {
+            for &mode in action.modes() {
+                for chord in &chords {
+                    if !(mode == Mode::Type && chord.is_plain_char()) {
+                        bindings.push((mode, *chord, action));
+                    }
+                }
+            }
+        }
+        Keymap { bindings }
+    }
+
+    /// `"vim"` or `{"preset": "vim", "bindings": {"abort": ["esc", "ctrl+c"], "toggle": "f3"}}`;
+    /// the keys given for an action replace the preset's
+    pub fn from_value(v: &Value) -> Result<Keymap, String> {
+        let preset = v.as_str().or_else(|| v["preset"].as_str()).unwrap_or("default");
+        let keys = preset_keys(preset)
+            .ok_or_else(|| format!("`keys.preset` must be one of {}, not \"{}\"", PRESETS.join(", "), preset))?;
+        let mut actions: Vec<(Action, Vec<KeyChord>)> =
+            keys.into_iter().map(|(action, chords)| (action, chords.into_iter().filter_map(KeyChord::parse).collect())).collect();
+        if let Some(bindings) = v["bindings"].as_object() {
+            for (name, chords) in bindings {
+                let action = Action::from_name(name).ok_or_else(|| format!("unknown action `keys.bindings.{}`", name))?;
+                let chords: Vec<&str> = match chords {
+                    Value::String(chord) => vec![chord.as_str()],
+                    Value::Array(chords) => chords.iter().filter_map(Value::as_str).collect(),
+                    _ => return Err(format!("`keys.bindings.{}` must be a key or an array of keys", name)),
+                };
+                let chords = chords
+                    .into_iter()
+                    .map(|chord| KeyChord::parse(chord).ok_or_else(|| format!("`keys.bindings.{}`: unknown key `{}`", name, chord)))
+                    .collect::<Result<Vec<_>, String>>()?;
+                match actions.iter_mut().find(|(a, _)| *a == action) {
+                    Some((_, keys)) => *keys = chords,
+                    None => actions.push((action, chords)),
+                }
+            }
+        }
+        Ok(Keymap::from_actions(actions))
+    }
+
+    /// Keys bound to `action` in `mode`
+    pub fn keys(&self, mode: Mode, action: Action) -> Vec<KeyChord> {
+        self.bindings.iter().filter(|(m, _, a)| *m == mode && *a == action).map(|(_, chord, _)| *chord).collect()
+    }
+
+    pub fn action(&self, mode: Mode, event: &KeyEvent) -> Option<Action> {
+        self.bindings.iter().find(|(m, chord, _)| *m == mode && chord.matches(event)).map(|(_, _, action)| *action)
+    }
+
+    /// Actions of `mode` with all of their keys, in binding order
+    pub fn bindings(&self, mode: Mode) -> Vec<(Action, Vec<KeyChord>)> {
+        let mut grouped: Vec<(Action, Vec<KeyChord>)> = vec![];
+        for (_, chord, action) in self.bindings.iter().filter(|(m, _, _)| *m == mode) {
+            match grouped.iter_mut().find(|(a, _)| a == action) {
+                Some((_, chords)) => chords.push(*chord),
+                None => grouped.push((*action, vec![*chord])),
+            }
+        }
+        grouped
+    }
+}
@@ -1,14 +1,34 @@
+pub mod bump;
+pub mod changelog;
+pub mod config;
+pub mod conventional;
+pub mod display;
+pub mod draft;
+pub mod gitmoji;
+pub mod glob;
+pub mod history;
+pub mod hook;
+pub mod i18n;
+pub mod keymap;
+pub mod lint;
+pub mod log;
+pub mod scope;
+pub mod source;
+pub mod template;
+pub mod theme;
+pub mod tracker;
+pub mod workspace;
+
+use i18n::Locale;
+
 #[derive(Debug)]
 pub struct CommitMessage {
     pub status: MessageType,
     pub content: Vec<String>,
+    /// Language of the prompts
+    pub locale: Locale,
 }
 
-const MISSIONID: &str = "请输入任务ID:";
-const CHANGETYPE: &str = "请选择变更类型:";
-const SCOPE: &str = "请输入变更范围:";
-const SUBJECT: &str = "请入变更概述:";
-
 
 #[derive(Debug)]
 pub enum MessageType {
@@ -29,12 +49,23 @@ impl MessageType {
     }
 }
 
+impl Default for CommitMessage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CommitMessage {
 
     pub fn new() -> CommitMessage {
+        CommitMessage::with_locale(Locale::detect())
+    }
+
+    pub fn with_locale(locale: Locale) -> CommitMessage {
         CommitMessage {
-            status: MessageType::Missionid(MISSIONID),
-            content: vec![]
+            status: MessageType::Missionid(locale.catalog().mission_id_prompt),
+            content: vec![],
+            locale,
         }
     }
 
@@ -43,10 +74,11 @@ impl CommitMessage {
     }
 
     pub fn next(& mut self){
+        let catalog = self.locale.catalog();
         self.status = match self.status {
-            MessageType::Missionid(_) => MessageType::ChangeType(CHANGETYPE),
-            MessageType::ChangeType(_) => MessageType::Scope(SCOPE),
-            MessageType::Scope(_) => MessageType::Subject(SUBJECT),
+            MessageType::Missionid(_) => MessageType::ChangeType(catalog.change_type_prompt),
+            MessageType::ChangeType(_) => MessageType::Scope(catalog.scope_prompt),
+            MessageType::Scope(_) => MessageType::Subject(catalog.subject_prompt),
             MessageType::Subject(s) => MessageType::Subject(s),
         };
     }
@@ -0,0 +1,99 @@
+use regex::Regex;
+
+use crate::{
+    config::Config,
+    template::{Template, MISSION_ID},
+};
+
+/// One rule a commit message breaks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Step the violation belongs to, `None` for whole-message rules
+    pub step: Option<String>,
+    pub message: String,
+}
+
+/// Checks commit messages against the template and rules of a [`Config`]
+pub struct Linter<'a> {
+    config: &'a Config,
+    template: Template,
+    mission_id: Option<Regex>,
+}
+
+impl<'a> Linter<'a> {
+    pub fn new(config: &'a Config) -> Result<Linter<'a>, regex::Error> {
+        let mission_id = match &config.rules.mission_id_pattern {
+            Some(pattern) => Some(Regex::new(pattern)?),
+            None => None,
+        };
+        Ok(Linter { config, template: config.template(), mission_id })
+    }
+
+    /// Merge commits and `fixup!`/`squash!` commits are not written with rcz
+    pub fn is_ignored(message: &str) -> bool {
+        let header = message.lines().next().unwrap_or_default();
+        header.starts_with("Merge ") || header.starts_with("fixup! ") || header.starts_with("squash! ")
+    }
+
+    pub fn lint(&self, message: &str) -> Vec<Violation> {
+        let mut violations = vec![];
+        let header = message.trim().lines().next().unwrap_or_default();
+        if let Some(max) = self.config.rules.header_max_length {
+            let length = header.chars().count();
+            if length > max {
+                violations.push(Violation {
+                    step: None,
+                    message: format!("header is {} characters long, at most {} allowed", length, max),
+                });
+            }
+        }
+
+        let answers = self.template.parse(message, &self.config.select_map);
+        for (step, answer) in self.template.steps().iter().zip(&answers) {
+            let required = match &self.config.rules.required {
+                Some(required) => required.contains(step),
+                None => Template::is_header(step),
+            };
+            if answer.is_empty() {
+                if !required {
+                    continue;
+                }
+                let message = match self.config.select_map.get(step) {
+                    Some(choices) => format!(
+                        "missing or not one of: {}",
+                        choices.iter().map(|(_, value)| value.as_str()).collect::<Vec<_>>().join(", ")
+                    ),
+                    None => "may not be empty".to_string(),
+                };
+                violations.push(Violation { step: Some(step.clone()), message });
+            } else if let Some(violation) = self.check(step, answer) {
+                violations.push(violation);
+            }
+        }
+        violations
+    }
+
+    /// Check one non-empty answer against the select list of its step and the MissionId pattern
+    pub fn check(&self, step: &str, answer: &str) -> Option<Violation> {
+        let violation = |message| Some(Violation { step: Some(step.to_string()), message });
+        // 命令生成的选项没有运行时无法检查
+        let choices = self.config.select_map.get(step).filter(|_| !self.config.command_sources.contains_key(step));
+        if let Some(choices) = choices {
+            if !choices.iter().any(|(_, value)| value == answer) {
+                let values = choices.iter().map(|(_, value)| value.as_str()).collect::<Vec<_>>().join(", ");
+                return violation(format!("`{}` is not one of: {}", answer, values));
+            }
+        }
+        match &self.mission_id {
+            Some(pattern) if step == MISSION_ID && !pattern.is_match(answer) => {
+                violation(format!("`{}` does not match `{}`", answer, pattern.as_str()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Drop the `#` comment lines git adds to the message file
+pub fn strip_comments(message: &str) -> String {
+    message.lines().filter(|line| !line.starts_with('#')).collect::<Vec<_>>().join("\n")
+}
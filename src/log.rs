@@ -0,0 +1,182 @@
+use std::process::Command;
+
+const FIELD_SEP: char = '\x1f';
+const RECORD_SEP: char = '\x1e';
+const LOG_FORMAT: &str = "--format=%h%x1f%an%x1f%ar%x1f%s%x1e";
+
+/// One commit as shown in the git log panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub hash: String,
+    pub author: String,
+    pub date: String,
+    pub header: String,
+}
+
+/// Which commits the log panel lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogScope {
+    /// Full history reachable from HEAD
+    All,
+    /// Only commits on the current branch that are not yet on its upstream
+    Branch,
+}
+
+impl LogScope {
+    pub fn toggle(self) -> LogScope {
+        match self {
+            LogScope::All => LogScope::Branch,
+            LogScope::Branch => LogScope::All,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LogScope::All => "all",
+            LogScope::Branch => "branch",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LogScope> {
+        match name {
+            "all" => Some(LogScope::All),
+            "branch" => Some(LogScope::Branch),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogConfig {
+    pub count: usize,
+    pub scope: LogScope,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig { count: 5, scope: LogScope::All }
+    }
+}
+
+/// Run `git log` for the given scope and parse its output;
+/// without an upstream [`LogScope::Branch`] falls back to all commits, see [`has_upstream`]
+pub fn read_log(config: &LogConfig) -> Vec<LogEntry> {
+    let count = format!("-n{}", config.count);
+    let mut args = vec!["log", &count, LOG_FORMAT];
+    if config.scope == LogScope::Branch && has_upstream() {
+        args.push("@{upstream}..HEAD");
+    }
+    match Command::new("git").args(&args).output() {
+        Ok(output) if output.status.success() => parse_log(&String::from_utf8_lossy(&output.stdout)),
+        _ => vec![],
+    }
+}
+
+/// Full message of the HEAD commit, `None` when there is no commit yet
+pub fn head_message() -> Option<String> {
+    let output = Command::new("git").args(["log", "-1", "--format=%B"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// `(hash, full message)` of every commit in `range` (e.g. `v1.0.0..HEAD`), newest first
+pub fn read_messages(range: &str) -> Result<Vec<(String, String)>, String> {
+    let output = Command::new("git")
+        .args(["log", "--format=%H%x1f%B%x1e", range])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split(RECORD_SEP)
+        .map(|record| record.trim_start_matches('\n'))
+        .filter_map(|record| record.split_once(FIELD_SEP))
+        .map(|(hash, message)| (hash.to_string(), message.trim_end().to_string()))
+        .collect())
+}
+
+/// Most recent tag reachable from `rev`
+pub fn latest_tag(rev: &str) -> Option<String> {
+    git_line(&["describe", "--tags", "--abbrev=0", rev])
+}
+
+/// Every tag of the repository
+pub fn tags() -> Vec<String> {
+    git_line(&["tag", "--list"]).map(|tags| tags.lines().map(str::to_string).collect()).unwrap_or_default()
+}
+
+/// Committer date of `rev` as `YYYY-MM-DD`
+pub fn commit_date(rev: &str) -> Option<String> {
+    git_line(&["log", "-1", "--format=%cs", rev])
+}
+
+/// The `Signed-off-by` trailer `git commit --signoff` adds for the configured identity
+pub fn signoff_trailer() -> Option<String> {
+    let name = git_line(&["config", "user.name"])?;
+    let email = git_line(&["config", "user.email"])?;
+    Some(format!("Signed-off-by: {} <{}>", name, email))
+}
+
+fn git_line(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether the current branch tracks an upstream branch
+pub fn has_upstream() -> bool {
+    Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "@{upstream}"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Parse the output of `git log` run with `LOG_FORMAT`
+pub fn parse_log(output: &str) -> Vec<LogEntry> {
+    output
+        .split(RECORD_SEP)
+        .map(|record| record.trim_start_matches('\n'))
+        .filter(|record| !record.is_empty())
+        .filter_map(|record| {
+            let mut fields = record.split(FIELD_SEP);
+            Some(LogEntry {
+                hash: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                header: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Split a commit header into tokens, flagging the ones found in `known`
+/// (e.g. the configured ChangeType and Scopes values).
+///
+/// Delimiters such as `(`, `)`, `:` and spaces are kept as unflagged tokens so
+/// the pieces join back into the original header.
+pub fn highlight<'a>(header: &'a str, known: &[&str]) -> Vec<(&'a str, bool)> {
+    let is_delimiter = |c: char| c.is_whitespace() || "():!,".contains(c);
+    let mut tokens = vec![];
+    let mut start = 0;
+    for (i, c) in header.char_indices() {
+        if is_delimiter(c) {
+            if start < i {
+                let word = &header[start..i];
+                tokens.push((word, known.contains(&word)));
+            }
+            tokens.push((&header[i..i + c.len_utf8()], false));
+            start = i + c.len_utf8();
+        }
+    }
+    if start < header.len() {
+        let word = &header[start..];
+        tokens.push((word, known.contains(&word)));
+    }
+    tokens
+}
@@ -2,7 +2,7 @@ use std::io;
 use std::fs::File;
 
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -14,24 +14,32 @@ use std::{
 
 use tui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Spans},
     widgets::{Block, Borders, List, ListItem, ListState},
     Frame, Terminal,
 };
 
+use git_message::{
+    i18n::Locale,
+    keymap::{Action, Keymap, Mode},
+};
+
 #[derive(Debug)]
 struct StatefulList<'a, T> {
     state: ListState,
     items: & 'a Vec<T>,
+    // 第一个可见的元素, tui的offset是私有的
+    offset: usize,
 }
 
 impl<'a, T> StatefulList<'a, T> {
-    fn with_items(items:& Vec<T>) -> StatefulList<T> {
+    fn with_items(items:& Vec<T>) -> StatefulList<'_, T> {
         StatefulList {
             state: ListState::default(),
             items,
+            offset: 0,
         }
     }
 
@@ -66,17 +74,35 @@ impl<'a, T> StatefulList<'a, T> {
     fn unselect(&mut self) {
         self.state.select(None);
     }
+
+    // 保证选中的元素在 height 行内可见
+    fn scroll_into_view(&mut self, height: usize) {
+        if let Some(i) = self.state.selected() {
+            if i < self.offset {
+                self.offset = i;
+            } else if i >= self.offset + height {
+                self.offset = i + 1 - height;
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
 struct App<'a> {
     items: StatefulList<'a, (&'a str, &'a str, usize)>,
+    keymap: Keymap,
+    // 列表所在区域, 用来判断点击了哪一项
+    list_area: Rect,
+    last_click: Option<(usize, Instant)>,
 }
 
 impl<'a> App <'a> {
-    fn new<'b>(items: & 'b Vec<(&str,&str, usize)>) -> App<'b> {
+    fn new<'b>(items: & 'b Vec<(&str,&str, usize)>, keymap: Keymap) -> App<'b> {
 			App {
-				items: StatefulList::with_items(items)
+				items: StatefulList::with_items(items),
+				keymap,
+				list_area: Rect::default(),
+				last_click: None,
 			}
 		}
 }
@@ -87,6 +113,17 @@ fn main() {
     // read the json file
     let f = File::open("custom.json").unwrap();
     let v: serde_json::Value = serde_json::from_reader(f).unwrap();
+    let keymap = match &v["keys"] {
+        serde_json::Value::Null => Keymap::default(),
+        keys => match Keymap::from_value(keys) {
+            Ok(keymap) => keymap,
+            Err(err) => {
+                eprintln!("custom.json: keys: {}", err);
+                std::process::exit(1);
+            }
+        },
+    };
+    let mouse = v["mouse"].as_bool().unwrap_or(true);
     
     // 将json转成数组
     let change_type = &v["ChangeType"];
@@ -110,15 +147,17 @@ fn main() {
 
     // 将json转成数组
     let mut command_map = vec![];
+    let locale = Locale::detect();
     commit_map.as_array().unwrap().iter().for_each(|message_object| {
         let mut type_key = "";
-        let mut placeholder = "";
+        let mut placeholder = String::new();
         message_object.as_object().unwrap().iter().for_each(|(key, value)| {
             if key == "type" {
                 type_key = value.as_str().unwrap();
             }
             if key == "placeholder" {
-                placeholder = value.as_str().unwrap();
+                // 提示可以按语言分别配置
+                placeholder = locale.pick(value).unwrap();
             }
         });
         command_map.push((type_key, placeholder));
@@ -129,8 +168,11 @@ fn main() {
         println!("{}: {}", key, value);
         let mut input = String::new();
         if key == "ChangeType" {
-            input = open_terminal(&array).unwrap().to_string();
-            input = array[input.parse::<usize>().unwrap()].1.to_string();
+            // 放弃时不提交
+            match open_terminal(&array, keymap.clone(), mouse).unwrap() {
+                Some(index) => input = array[index].1.to_string(),
+                None => return,
+            }
         } else {
             io::stdin().read_line(&mut input).unwrap();
         }
@@ -145,15 +187,18 @@ fn main() {
         .expect("failed to execute process");
 }
 
-fn open_terminal(array: & Vec<(&str, &str, usize)>) -> Result<usize, Box<dyn Error>> {
+fn open_terminal(array: & Vec<(&str, &str, usize)>, keymap: Keymap, mouse: bool) -> Result<Option<usize>, Box<dyn Error>> {
     // enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen)?;
+    if mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
      // create app and run it
     let tick_rate = Duration::from_millis(250);
-    let app = App::new(array);
+    let app = App::new(array, keymap);
     let res = run_app(&mut terminal, app, tick_rate);
     // restore terminal
     disable_raw_mode()?;
@@ -172,11 +217,14 @@ fn open_terminal(array: & Vec<(&str, &str, usize)>) -> Result<usize, Box<dyn Err
     Ok(res.unwrap())
 }
 
+// 两次点击同一项的最长间隔
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     mut app: App,
     tick_rate: Duration,
-) -> io::Result<usize> {
+) -> io::Result<Option<usize>> {
     let mut last_tick = Instant::now();
     loop {
         terminal.draw(|f| ui(f, &mut app))?;
@@ -185,14 +233,40 @@ fn run_app<B: Backend>(
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Enter => return Ok(app.items.state.selected().unwrap_or_else(|| 0)),
-                    KeyCode::Left => app.items.unselect(),
-                    KeyCode::Down => app.items.next(),
-                    KeyCode::Up => app.items.previous(),
+            match event::read()? {
+                Event::Key(key) => match (app.keymap.action(Mode::Select, &key), key.code) {
+                    // 没有绑定时 q 仍然确认
+                    (Some(Action::Submit), _) | (None, KeyCode::Char('q')) => {
+                        return Ok(Some(app.items.state.selected().unwrap_or(0)))
+                    }
+                    (Some(Action::Abort), _) => return Ok(None),
+                    (Some(Action::Back), _) => app.items.unselect(),
+                    (Some(Action::Next), _) => app.items.next(),
+                    (Some(Action::Previous), _) => app.items.previous(),
                     _ => {}
-                }
+                },
+                // 滚轮切换选项, 单击选中, 双击确认
+                Event::Mouse(mouse) => match mouse.kind {
+                    MouseEventKind::ScrollDown => app.items.next(),
+                    MouseEventKind::ScrollUp => app.items.previous(),
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        let area = app.list_area;
+                        // 去掉边框
+                        let inside = mouse.column > area.x && mouse.column + 1 < area.x + area.width
+                            && mouse.row > area.y && mouse.row + 1 < area.y + area.height;
+                        let index = app.items.offset + mouse.row.saturating_sub(area.y + 1) as usize;
+                        if inside && index < app.items.items.len() {
+                            let double = matches!(app.last_click, Some((last, at)) if last == index && at.elapsed() < DOUBLE_CLICK);
+                            if double {
+                                return Ok(Some(index));
+                            }
+                            app.items.state.select(Some(index));
+                            app.last_click = Some((index, Instant::now()));
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
             }
         }
         if last_tick.elapsed() >= tick_rate {
@@ -208,11 +282,15 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(f.size());
 
+    app.items.scroll_into_view(chunks[0].height.saturating_sub(2) as usize);
+    app.list_area = chunks[0];
+
     // Iterate through all elements in the `items` app and append some debug text to it.
     let items: Vec<ListItem> = app
         .items
         .items
         .iter()
+        .skip(app.items.offset)
         .map(|i| {
             let lines = vec![Spans::from(i.0)];
             // for _ in 0..i.2 {
@@ -236,10 +314,12 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .highlight_symbol(">> ");
 
     // We can now render the item list
-    f.render_stateful_widget(items, chunks[0], &mut app.items.state);
+    let mut state = ListState::default();
+    state.select(app.items.state.selected().and_then(|i| i.checked_sub(app.items.offset)));
+    f.render_stateful_widget(items, chunks[0], &mut state);
 
     let block = Block::default()
-        .title(app.items.state.selected().unwrap_or_else(|| 0).to_string())
+        .title(app.items.state.selected().unwrap_or(0).to_string())
         .borders(Borders::ALL);
     f.render_widget(block, chunks[1]);
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+use std::process::Command;
+
+use crate::glob;
+
+/// `scopeRules` entry: staged paths matching `pattern` suggest `scope`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeRule {
+    pub pattern: String,
+    pub scope: String,
+}
+
+/// A suggested scope and the staged files that triggered it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub scope: String,
+    pub files: Vec<String>,
+}
+
+/// Paths in the index that differ from HEAD
+pub fn staged_paths() -> Vec<String> {
+    match Command::new("git").args(["diff", "--cached", "--name-only"]).output() {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Scopes whose rules match any of `paths`, the ones matching most files first
+pub fn suggest(rules: &[ScopeRule], paths: &[String]) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = vec![];
+    for rule in rules {
+        let files = paths.iter().filter(|path| glob::matches(&rule.pattern, path)).cloned();
+        match suggestions.iter_mut().find(|s| s.scope == rule.scope) {
+            Some(suggestion) => {
+                for file in files {
+                    if !suggestion.files.contains(&file) {
+                        suggestion.files.push(file);
+                    }
+                }
+            }
+            None => suggestions.push(Suggestion { scope: rule.scope.clone(), files: files.collect() }),
+        }
+    }
+    suggestions.retain(|s| !s.files.is_empty());
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.files.len()));
+    suggestions
+}
+
+/// Move the suggested choices to the top of a `(name, value)` list, keeping the rest in order
+pub fn reorder(choices: &mut [(String, String)], suggestions: &[Suggestion]) {
+    let rank = |value: &str| suggestions.iter().position(|s| s.scope == value).unwrap_or(usize::MAX);
+    choices.sort_by_key(|(_, value)| rank(value));
+}
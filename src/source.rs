@@ -0,0 +1,157 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    error::Error,
+    fs,
+    hash::{Hash, Hasher},
+    io::Read,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::{json, Value};
+
+use crate::hook::git_path;
+
+const CACHE_DIR: &str = "rcz/cache";
+/// Start of the name of the choice a failed command is shown as
+const ERROR_PREFIX: &str = "! ";
+
+/// A select list filled from the output of an external command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandSource {
+    /// Program and arguments, run without a shell
+    pub command: Vec<String>,
+    pub timeout: Duration,
+    /// How long a successful result is reused, zero disables the cache
+    pub cache: Duration,
+}
+
+impl CommandSource {
+    /// `{"command": [...], "timeout": 5, "cache": 300}`, times in seconds
+    pub fn from_value(v: &Value) -> Option<CommandSource> {
+        let command: Vec<String> = v["command"].as_array()?.iter().filter_map(|s| s.as_str()).map(str::to_string).collect();
+        if command.is_empty() {
+            return None;
+        }
+        Some(CommandSource {
+            command,
+            timeout: Duration::from_secs(v["timeout"].as_u64().unwrap_or(5)),
+            cache: Duration::from_secs(v["cache"].as_u64().unwrap_or(300)),
+        })
+    }
+
+    /// The choices from the cache or a fresh run; a failure becomes a single
+    /// choice describing the error, with an empty value
+    pub fn choices(&self) -> Vec<(String, String)> {
+        if let Some(choices) = self.cached() {
+            return choices;
+        }
+        match self.run() {
+            Ok(choices) => {
+                self.store(&choices);
+                choices
+            }
+            Err(err) => vec![(format!("{}{}: {}", ERROR_PREFIX, self.command.join(" "), err), String::new())],
+        }
+    }
+
+    /// Whether `choice` is the error of a failed command, which can not be selected
+    pub fn is_error(choice: &(String, String)) -> bool {
+        choice.1.is_empty() && choice.0.starts_with(ERROR_PREFIX)
+    }
+
+    /// Run the command, killing it after the timeout
+    pub fn run(&self) -> Result<Vec<(String, String)>, String> {
+        let mut child = Command::new(&self.command[0])
+            .args(&self.command[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| err.to_string())?;
+        let mut stdout = child.stdout.take().ok_or("no stdout")?;
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut out = String::new();
+            let _ = tx.send(stdout.read_to_string(&mut out).map(|_| out));
+        });
+        match rx.recv_timeout(self.timeout) {
+            Ok(Ok(out)) => {
+                let status = child.wait().map_err(|err| err.to_string())?;
+                if !status.success() {
+                    return Err(format!("exited with {}", status));
+                }
+                parse_output(&out)
+            }
+            Ok(Err(err)) => Err(err.to_string()),
+            Err(_) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                Err(format!("timed out after {}s", self.timeout.as_secs()))
+            }
+        }
+    }
+
+    fn cache_path(&self) -> Result<PathBuf, Box<dyn Error>> {
+        let mut hasher = DefaultHasher::new();
+        self.command.hash(&mut hasher);
+        git_path(&format!("{}/{:016x}.json", CACHE_DIR, hasher.finish()))
+    }
+
+    fn cached(&self) -> Option<Vec<(String, String)>> {
+        if self.cache.is_zero() {
+            return None;
+        }
+        let v: Value = serde_json::from_str(&fs::read_to_string(self.cache_path().ok()?).ok()?).ok()?;
+        if now().saturating_sub(v["time"].as_u64()?) > self.cache.as_secs() {
+            return None;
+        }
+        choices(&v["choices"])
+    }
+
+    fn store(&self, choices: &[(String, String)]) {
+        if self.cache.is_zero() {
+            return;
+        }
+        let Ok(path) = self.cache_path() else { return };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let choices: Vec<Value> = choices.iter().map(|(name, value)| json!({"name": name, "value": value})).collect();
+        let _ = fs::write(path, json!({"command": self.command, "time": now(), "choices": choices}).to_string());
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn choices(v: &Value) -> Option<Vec<(String, String)>> {
+    v.as_array()?
+        .iter()
+        .map(|o| {
+            let value = o["value"].as_str()?.to_string();
+            let name = o["name"].as_str().map(str::to_string).unwrap_or_else(|| value.clone());
+            Some((name, value))
+        })
+        .collect()
+}
+
+/// A JSON array of `{"name", "value"}` objects, or one `value<TAB>name` per line
+pub fn parse_output(out: &str) -> Result<Vec<(String, String)>, String> {
+    if out.trim_start().starts_with('[') {
+        let v: Value = serde_json::from_str(out).map_err(|err| format!("invalid JSON: {}", err))?;
+        return choices(&v).ok_or_else(|| "every item needs a string `value`".to_string());
+    }
+    Ok(out
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| match line.split_once('\t') {
+            Some((value, name)) => (name.to_string(), value.to_string()),
+            None => (line.to_string(), line.to_string()),
+        })
+        .collect())
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use crate::gitmoji;
+
+/// Step types the tools built on the template look for
+pub const MISSION_ID: &str = "MissionId";
+pub const CHANGE_TYPE: &str = "ChangeType";
+pub const SCOPES: &str = "Scopes";
+pub const SUBJECT: &str = "Subject";
+/// Step type whose answer is written as the commit body
+pub const BODY: &str = "Body";
+/// Step type whose answer is written as the trailing footer/trailer block
+pub const FOOTERS: &str = "Footers";
+
+/// How the answers of the configured steps are turned into a commit message.
+///
+/// Every step except `Body` and `Footers` goes into the header, joined by a
+/// single space in step order. `Body` and `Footers` follow as paragraphs
+/// separated by a blank line. Select values with an `emoji` are written
+/// after their emoji, e.g. `:sparkles: feat`.
+#[derive(Debug, Clone)]
+pub struct Template {
+    steps: Vec<String>,
+    /// Emoji written before a value, keyed by step type and value
+    emoji: HashMap<String, HashMap<String, String>>,
+}
+
+impl Template {
+    /// `steps` are the step types in commit order (the `type` of each `messages` entry)
+    pub fn new(steps: Vec<String>) -> Template {
+        Template { steps, emoji: HashMap::new() }
+    }
+
+    pub fn with_emoji(mut self, emoji: HashMap<String, HashMap<String, String>>) -> Template {
+        self.emoji = emoji;
+        self
+    }
+
+    pub fn steps(&self) -> &[String] {
+        &self.steps
+    }
+
+    pub fn is_header(step: &str) -> bool {
+        step != BODY && step != FOOTERS
+    }
+
+    /// Render the answers, one per step, into the commit message
+    pub fn render(&self, answers: &[String]) -> String {
+        let answer = |step: &str| {
+            self.steps
+                .iter()
+                .zip(answers)
+                .filter(|(s, a)| s.as_str() == step && !a.trim().is_empty())
+                .map(|(_, a)| a.trim())
+                .collect::<Vec<_>>()
+        };
+        let header = self
+            .steps
+            .iter()
+            .zip(answers)
+            .filter(|(s, a)| Template::is_header(s) && !a.trim().is_empty())
+            .map(|(s, a)| match self.emoji.get(s).and_then(|e| e.get(a.trim())) {
+                Some(emoji) => format!("{} {}", emoji, a.trim()),
+                None => a.trim().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut paragraphs = vec![header];
+        paragraphs.extend(answer(BODY).into_iter().map(str::to_string));
+        paragraphs.extend(answer(FOOTERS).into_iter().map(str::to_string));
+        paragraphs.join("\n\n")
+    }
+
+    /// Reverse [`Template::render`]: split a commit message back into one answer per step.
+    ///
+    /// `select_map` holds the allowed values of the select steps; it is used to
+    /// tell which header word belongs to which step when optional steps were
+    /// left empty. The last header step takes the rest of the header.
+    pub fn parse(&self, message: &str, select_map: &HashMap<String, Vec<(String, String)>>) -> Vec<String> {
+        let (header, mut paragraphs) = split_message(message);
+        let is_value = |step: &str, word: &str| {
+            select_map.get(step).is_some_and(|choices| choices.iter().any(|(_, value)| value == word))
+        };
+
+        let header_steps: Vec<usize> = (0..self.steps.len()).filter(|&i| Template::is_header(&self.steps[i])).collect();
+        let mut answers = vec![String::new(); self.steps.len()];
+        let mut words = header.split_whitespace().peekable();
+        for (n, &i) in header_steps.iter().enumerate() {
+            let step = self.steps[i].as_str();
+            let next_step = header_steps.get(n + 1).map(|&j| self.steps[j].as_str());
+            if select_map.contains_key(step) {
+                // 跳过值前面的emoji(两种写法都可以)
+                if self.emoji.contains_key(step) && words.peek().is_some_and(|word| gitmoji::is_emoji(word)) {
+                    let mut ahead = words.clone();
+                    ahead.next();
+                    if ahead.peek().is_some_and(|word| is_value(step, word)) {
+                        words.next();
+                    }
+                }
+                if let Some(word) = words.peek() {
+                    if is_value(step, word) {
+                        answers[i] = words.next().unwrap().to_string();
+                    }
+                }
+            } else if next_step.is_none() {
+                answers[i] = words.by_ref().collect::<Vec<_>>().join(" ");
+            } else if let Some(word) = words.peek() {
+                // 下一步是选择项且当前词匹配时, 说明本步骤留空
+                let skipped = next_step.is_some_and(|next| is_value(next, word));
+                if !skipped {
+                    answers[i] = words.next().unwrap().to_string();
+                }
+            }
+        }
+
+        if let Some(i) = self.steps.iter().position(|s| s == FOOTERS) {
+            if paragraphs.last().is_some_and(|p| is_trailer_block(p)) {
+                answers[i] = paragraphs.pop().unwrap().to_string();
+            }
+        }
+        if let Some(i) = self.steps.iter().position(|s| s == BODY) {
+            answers[i] = paragraphs.join("\n\n");
+        }
+        answers
+    }
+
+    /// The part of `message` after the header that [`Template::parse`] has no step
+    /// for, e.g. the body and trailers when there are no `Body`/`Footers` steps
+    pub fn unparsed(&self, message: &str) -> String {
+        let (_, mut paragraphs) = split_message(message);
+        if self.steps.iter().any(|s| s == BODY) {
+            return String::new();
+        }
+        if self.steps.iter().any(|s| s == FOOTERS) && paragraphs.last().is_some_and(|p| is_trailer_block(p)) {
+            paragraphs.pop();
+        }
+        paragraphs.join("\n\n")
+    }
+}
+
+/// The header line and the non-empty paragraphs after it
+fn split_message(message: &str) -> (&str, Vec<&str>) {
+    let message = message.trim();
+    let (header, rest) = match message.split_once('\n') {
+        Some((header, rest)) => (header, rest.trim()),
+        None => (message, ""),
+    };
+    (header, rest.split("\n\n").map(str::trim).filter(|p| !p.is_empty()).collect())
+}
+
+/// Whether every line of `paragraph` looks like a git trailer (`Token: value` or `Token #value`)
+pub fn is_trailer_block(paragraph: &str) -> bool {
+    paragraph.lines().all(|line| {
+        let token_end = line.find(": ").or_else(|| line.find(" #"));
+        match token_end {
+            Some(end) => {
+                let token = &line[..end];
+                !token.is_empty()
+                    && (token == "BREAKING CHANGE" || token.chars().all(|c| c.is_alphanumeric() || c == '-'))
+            }
+            None => false,
+        }
+    })
+}
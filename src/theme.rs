@@ -0,0 +1,188 @@
+use serde_json::Value;
+use tui::style::{Color, Modifier, Style};
+
+/// Named style of every widget that is drawn in colour
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Entries of a select list
+    pub item: Style,
+    /// The highlighted entry
+    pub highlight: Style,
+    /// Marker of a suggested scope
+    pub suggestion: Style,
+    /// Commit hash in the log panel
+    pub hash: Style,
+    /// Commit date in the log panel, the `Signed-off-by` preview
+    pub dim: Style,
+    /// Configured type/scope values in the log panel
+    pub known: Style,
+    /// Keys in the hints
+    pub key: Style,
+    pub warning: Style,
+    pub error: Style,
+}
+
+/// Style names accepted in `theme.styles`
+pub const STYLE_NAMES: [&str; 9] = ["item", "highlight", "suggestion", "hash", "dim", "known", "key", "warning", "error"];
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            item: Style::default().fg(Color::Black).bg(Color::White),
+            highlight: Style::default().bg(Color::LightGreen).add_modifier(Modifier::BOLD),
+            suggestion: Style::default().fg(Color::Blue),
+            hash: Style::default().fg(Color::Rgb(193, 156, 0)),
+            dim: Style::default().add_modifier(Modifier::DIM),
+            known: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            key: Style::default().add_modifier(Modifier::BOLD),
+            warning: Style::default().fg(Color::Yellow),
+            error: Style::default().fg(Color::Red),
+        }
+    }
+
+    /// Only the 16 basic colours, readable on a light background
+    pub fn light() -> Theme {
+        Theme {
+            item: Style::default(),
+            highlight: Style::default().fg(Color::White).bg(Color::Blue).add_modifier(Modifier::BOLD),
+            suggestion: Style::default().fg(Color::Magenta),
+            hash: Style::default().fg(Color::Magenta),
+            dim: Style::default().add_modifier(Modifier::DIM),
+            known: Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+            key: Style::default().add_modifier(Modifier::BOLD),
+            warning: Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            error: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        }
+    }
+
+    pub fn high_contrast() -> Theme {
+        Theme {
+            item: Style::default().fg(Color::White).bg(Color::Black),
+            highlight: Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD),
+            suggestion: Style::default().fg(Color::LightCyan).add_modifier(Modifier::BOLD),
+            hash: Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            dim: Style::default(),
+            known: Style::default().fg(Color::LightCyan).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            key: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            warning: Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            error: Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+        }
+    }
+
+    pub fn from_preset(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// `"light"` or `{"preset": "light", "styles": {"highlight": {"fg": "black", "bg": "#ffd700", "bold": true}}}`
+    pub fn from_value(v: &Value) -> Result<Theme, String> {
+        let preset = v.as_str().or_else(|| v["preset"].as_str()).unwrap_or("dark");
+        let mut theme = Theme::from_preset(preset)
+            .ok_or_else(|| format!("`theme.preset` must be \"dark\", \"light\" or \"high-contrast\", not \"{}\"", preset))?;
+        if let Some(styles) = v["styles"].as_object() {
+            for (name, spec) in styles {
+                let style = theme.style_mut(name).ok_or_else(|| format!("unknown style `theme.styles.{}`", name))?;
+                *style = patch(*style, spec).map_err(|err| format!("`theme.styles.{}`: {}", name, err))?;
+            }
+        }
+        Ok(theme)
+    }
+
+    fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        match name {
+            "item" => Some(&mut self.item),
+            "highlight" => Some(&mut self.highlight),
+            "suggestion" => Some(&mut self.suggestion),
+            "hash" => Some(&mut self.hash),
+            "dim" => Some(&mut self.dim),
+            "known" => Some(&mut self.known),
+            "key" => Some(&mut self.key),
+            "warning" => Some(&mut self.warning),
+            "error" => Some(&mut self.error),
+            _ => None,
+        }
+    }
+
+    /// The theme without colours (`NO_COLOR`, `--no-color`); the highlight is reversed to stay visible
+    pub fn without_color(mut self) -> Theme {
+        for name in STYLE_NAMES {
+            if let Some(style) = self.style_mut(name) {
+                style.fg = None;
+                style.bg = None;
+            }
+        }
+        self.highlight = self.highlight.add_modifier(Modifier::REVERSED);
+        self
+    }
+}
+
+/// Apply `{"fg", "bg", "bold", "dim", "italic", "underline", "reversed"}` on top of `style`
+fn patch(mut style: Style, spec: &Value) -> Result<Style, String> {
+    if let Some(fg) = spec.get("fg") {
+        style.fg = Some(color(fg)?);
+    }
+    if let Some(bg) = spec.get("bg") {
+        style.bg = Some(color(bg)?);
+    }
+    let modifiers = [
+        ("bold", Modifier::BOLD),
+        ("dim", Modifier::DIM),
+        ("italic", Modifier::ITALIC),
+        ("underline", Modifier::UNDERLINED),
+        ("reversed", Modifier::REVERSED),
+    ];
+    for (name, modifier) in modifiers {
+        match spec.get(name).and_then(Value::as_bool) {
+            Some(true) => style = style.add_modifier(modifier),
+            Some(false) => style = style.remove_modifier(modifier),
+            None => {}
+        }
+    }
+    Ok(style)
+}
+
+/// A colour name (`lightgreen`, `dark-gray`), `#rrggbb` or a 256-colour index
+pub fn color(v: &Value) -> Result<Color, String> {
+    if let Some(index) = v.as_u64() {
+        return u8::try_from(index).map(Color::Indexed).map_err(|_| format!("colour index {} is out of range", index));
+    }
+    let name = v.as_str().ok_or("colours are names, \"#rrggbb\" or 0-255")?;
+    if let Some(hex) = name.strip_prefix('#') {
+        let channel = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+        return match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Color::Rgb(r, g, b)),
+            _ => Err(format!("`{}` is not a #rrggbb colour", name)),
+        };
+    }
+    let color = match name.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return Err(format!("unknown colour `{}`", name)),
+    };
+    Ok(color)
+}
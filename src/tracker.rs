@@ -0,0 +1,101 @@
+use std::{
+    io::{ErrorKind, Write},
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use serde_json::Value;
+
+/// Issue tracker the `MissionId` step is checked against, see [`lookup`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackerConfig {
+    /// Issue endpoint, `{id}` is replaced by the entered ID,
+    /// e.g. `https://jira.example.com/rest/api/2/issue/{id}`
+    pub url: String,
+    /// Environment variable holding the API token
+    pub token_env: Option<String>,
+    /// Request header carrying the token, `{token}` is replaced
+    pub auth_header: String,
+    /// Dotted path of the title in the response, e.g. `fields.summary` (Jira) or `title` (GitLab/GitHub)
+    pub title_field: String,
+    pub timeout: Duration,
+}
+
+impl TrackerConfig {
+    pub fn from_value(v: &Value) -> Option<TrackerConfig> {
+        Some(TrackerConfig {
+            url: v["url"].as_str()?.to_string(),
+            token_env: v["tokenEnv"].as_str().map(str::to_string),
+            auth_header: v["authHeader"].as_str().unwrap_or("Authorization: Bearer {token}").to_string(),
+            title_field: v["titleField"].as_str().unwrap_or("fields.summary").to_string(),
+            timeout: Duration::from_secs(v["timeout"].as_u64().unwrap_or(5)),
+        })
+    }
+}
+
+/// Result of looking a ticket up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ticket {
+    Found { id: String, title: String },
+    /// The tracker answered that the ID does not exist
+    NotFound { id: String },
+    /// No answer (offline, timeout, unauthorized ...); the ID is accepted with a warning
+    Unavailable { id: String, reason: String },
+}
+
+/// Fetch the ticket with `curl`; the token is passed on stdin so it does not show up in `ps`
+pub fn lookup(config: &TrackerConfig, id: &str) -> Ticket {
+    let url = config.url.replace("{id}", &encode(id));
+    let token = config.token_env.as_deref().and_then(|name| std::env::var(name).ok());
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--location", "--max-time"])
+        .arg(config.timeout.as_secs().max(1).to_string())
+        .args(["--header", "Accept: application/json", "--write-out", "\n%{http_code}"]);
+    if token.is_some() {
+        command.args(["--header", "@-"]);
+    }
+    let child = command.arg(&url).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn();
+    let output = child.and_then(|mut child| {
+        if let (Some(token), Some(mut stdin)) = (&token, child.stdin.take()) {
+            writeln!(stdin, "{}", config.auth_header.replace("{token}", token))?;
+        }
+        child.wait_with_output()
+    });
+    let unavailable = |reason: String| Ticket::Unavailable { id: id.to_string(), reason };
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => return unavailable(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(err) if err.kind() == ErrorKind::NotFound => return unavailable("curl is not installed".to_string()),
+        Err(err) => return unavailable(format!("curl: {}", err)),
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    match status.trim() {
+        "404" | "410" => Ticket::NotFound { id: id.to_string() },
+        status if status.starts_with('2') => {
+            let v: Value = serde_json::from_str(body).unwrap_or_default();
+            Ticket::Found { id: id.to_string(), title: title_at(&v, &config.title_field).unwrap_or_default() }
+        }
+        status => unavailable(format!("HTTP {}", status)),
+    }
+}
+
+/// String at a dotted path such as `fields.summary`; numeric parts index arrays
+pub fn title_at(v: &Value, path: &str) -> Option<String> {
+    let value = path.split('.').try_fold(v, |v, key| match key.parse::<usize>() {
+        Ok(i) if v.is_array() => v.get(i),
+        _ => v.get(key),
+    })?;
+    value.as_str().map(str::to_string)
+}
+
+/// Percent-encode everything but unreserved characters, IDs such as `#12` stay in the path
+fn encode(id: &str) -> String {
+    id.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,173 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::glob;
+
+/// Where the choices of a select list are discovered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScopeSource {
+    /// `[workspace] members` of Cargo.toml
+    CargoWorkspace,
+    /// `workspaces` of package.json
+    NpmWorkspaces,
+    /// Directories matching a glob such as `packages/*`
+    Directories(String),
+}
+
+impl ScopeSource {
+    pub fn from_name(name: &str, directories: Option<&str>) -> Option<ScopeSource> {
+        match name {
+            "cargo-workspace" => Some(ScopeSource::CargoWorkspace),
+            "npm-workspaces" => Some(ScopeSource::NpmWorkspaces),
+            "directories" => directories.map(|pattern| ScopeSource::Directories(pattern.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// `(name, value)` choices for every member found under `root`;
+/// the value is the package name, the name also shows its directory
+pub fn discover(source: &ScopeSource, root: &Path) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let members = match source {
+        ScopeSource::CargoWorkspace => {
+            let content = fs::read_to_string(root.join("Cargo.toml")).map_err(|err| format!("Cargo.toml: {}", err))?;
+            expand(root, &cargo_members(&content))
+                .into_iter()
+                .map(|dir| {
+                    let name = fs::read_to_string(root.join(&dir).join("Cargo.toml")).ok().and_then(|c| cargo_package_name(&c));
+                    (dir, name)
+                })
+                .collect::<Vec<_>>()
+        }
+        ScopeSource::NpmWorkspaces => {
+            let content = fs::read_to_string(root.join("package.json")).map_err(|err| format!("package.json: {}", err))?;
+            let v: Value = serde_json::from_str(&content).map_err(|err| format!("package.json: {}", err))?;
+            expand(root, &npm_workspaces(&v))
+                .into_iter()
+                .map(|dir| {
+                    let package = fs::read_to_string(root.join(&dir).join("package.json")).ok();
+                    let package: Option<Value> = package.and_then(|c| serde_json::from_str(&c).ok());
+                    let name = package.and_then(|v| v["name"].as_str().map(npm_scope));
+                    (dir, name)
+                })
+                .collect()
+        }
+        ScopeSource::Directories(pattern) => expand(root, std::slice::from_ref(pattern)).into_iter().map(|dir| (dir, None)).collect(),
+    };
+    Ok(members
+        .into_iter()
+        .map(|(dir, name)| {
+            // 没有包名时使用目录名
+            let value = name.unwrap_or_else(|| dir.rsplit('/').next().unwrap_or_default().to_string());
+            (format!("{} ({})", value, dir), value)
+        })
+        .collect())
+}
+
+/// Entries of `members = [...]` in the `[workspace]` section, which may span several lines
+pub fn cargo_members(content: &str) -> Vec<String> {
+    let mut section = "";
+    let mut members = String::new();
+    let mut in_members = false;
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if !in_members && line.starts_with('[') {
+            section = line;
+            continue;
+        }
+        if section == "[workspace]" && !in_members {
+            if let Some(value) = line.strip_prefix("members") {
+                if let Some(value) = value.trim_start().strip_prefix('=') {
+                    in_members = true;
+                    members.clear();
+                    members.push_str(value);
+                }
+            }
+        } else if in_members {
+            members.push_str(line);
+        }
+        if in_members && members.contains(']') {
+            in_members = false;
+        }
+    }
+    quoted(&members)
+}
+
+/// `name = "..."` of the `[package]` section
+pub fn cargo_package_name(content: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package {
+            if let Some(value) = line.strip_prefix("name") {
+                if value.trim_start().starts_with('=') {
+                    return quoted(value).into_iter().next();
+                }
+            }
+        }
+    }
+    None
+}
+
+/// `workspaces` as an array or as `{"packages": [...]}` (yarn)
+pub fn npm_workspaces(v: &Value) -> Vec<String> {
+    let workspaces = match &v["workspaces"] {
+        Value::Object(_) => &v["workspaces"]["packages"],
+        workspaces => workspaces,
+    };
+    workspaces.as_array().map(|items| items.iter().filter_map(|s| s.as_str()).map(str::to_string).collect()).unwrap_or_default()
+}
+
+/// `@acme/accounts` -> `accounts`
+fn npm_scope(name: &str) -> String {
+    match name.strip_prefix('@') {
+        Some(name) => name.split_once('/').map(|(_, name)| name).unwrap_or(name).to_string(),
+        None => name.to_string(),
+    }
+}
+
+/// Every double-quoted string in `text`
+fn quoted(text: &str) -> Vec<String> {
+    text.split('"').skip(1).step_by(2).map(str::to_string).collect()
+}
+
+/// Directories under `root` matching any of the globs, as sorted `/`-separated relative paths
+fn expand(root: &Path, patterns: &[String]) -> Vec<String> {
+    let mut dirs = vec![];
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        let depth = if pattern.contains("**") { usize::MAX } else { pattern.split('/').count() };
+        walk(root, PathBuf::new(), depth, &mut |dir| {
+            if glob::matches(pattern, dir) && !dirs.iter().any(|d| d == dir) {
+                dirs.push(dir.to_string());
+            }
+        });
+    }
+    dirs.sort();
+    dirs
+}
+
+fn walk(root: &Path, dir: PathBuf, depth: usize, visit: &mut dyn FnMut(&str)) {
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(root.join(&dir)) else { return };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // 跳过隐藏目录和构建/依赖目录
+        if name.starts_with('.') || name == "target" || name == "node_modules" || !entry.path().is_dir() {
+            continue;
+        }
+        let path = dir.join(&name);
+        let relative = path.to_string_lossy().replace('\\', "/");
+        visit(&relative);
+        walk(root, path, depth - 1, visit);
+    }
+}
@@ -0,0 +1,41 @@
+use git_message::bump::{latest_version, package_name, set_lock_version, set_version, Level, Version};
+
+#[test]
+fn picks_highest_tag_and_bumps() {
+    let tags = ["v0.9.0", "v0.10.1", "v1.0.0-rc.1", "release-2", "v0.10.0"];
+    let (tag, version) = latest_version(tags.iter().copied(), "v").unwrap();
+    assert_eq!(tag, "v0.10.1");
+    assert_eq!(version.bump(Level::Patch).to_string(), "0.10.2");
+    assert_eq!(version.bump(Level::Minor).to_string(), "0.11.0");
+    assert_eq!(version.bump(Level::Major).to_string(), "1.0.0");
+}
+
+#[test]
+fn rewrites_only_the_package_version() {
+    let version = Version { major: 1, minor: 2, patch: 0 };
+    let cargo = "[package]\nname = \"x\"\nversion = \"1.1.0\"\n\n[dependencies]\nserde = { version = \"1\" }\n";
+    assert_eq!(
+        set_version("Cargo.toml", cargo, &version).unwrap(),
+        "[package]\nname = \"x\"\nversion = \"1.2.0\"\n\n[dependencies]\nserde = { version = \"1\" }\n"
+    );
+    let npm = "{\n  \"name\": \"rcz-cli\",\n  \"version\": \"1.0.0\",\n  \"main\": \"index.js\"\n}";
+    assert_eq!(
+        set_version("package.json", npm, &version).unwrap(),
+        "{\n  \"name\": \"rcz-cli\",\n  \"version\": \"1.2.0\",\n  \"main\": \"index.js\"\n}"
+    );
+    assert_eq!(set_version("Cargo.toml", "[workspace]\nmembers = []\n", &version), None);
+}
+
+#[test]
+fn rewrites_the_local_package_in_cargo_lock() {
+    let version = Version { major: 1, minor: 2, patch: 0 };
+    assert_eq!(package_name("[package]\nname = \"demo\"\nversion = \"1.1.0\"\n"), Some("demo".to_string()));
+    assert_eq!(package_name("[workspace]\nmembers = []\n"), None);
+
+    let lock = "version = 4\n\n[[package]]\nname = \"demo\"\nversion = \"1.0.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n[[package]]\nname = \"demo\"\nversion = \"1.1.0\"\n";
+    assert_eq!(
+        set_lock_version(lock, "demo", &version).unwrap(),
+        "version = 4\n\n[[package]]\nname = \"demo\"\nversion = \"1.0.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n[[package]]\nname = \"demo\"\nversion = \"1.2.0\"\n"
+    );
+    assert_eq!(set_lock_version(lock, "other", &version), None);
+}
@@ -0,0 +1,43 @@
+use git_message::{changelog, config::Config};
+use serde_json::json;
+
+#[test]
+fn groups_by_type_and_scope() {
+    let config = Config::from_value(&json!({
+        "ChangeType": [
+            {"value": "feat", "name": "feat:     A new feature"},
+            {"value": "fix", "name": "fix:      A bug fix"},
+            {"value": "chore", "name": "chore:    Build process"}
+        ],
+        "Scopes": [{"value": "accounts", "name": "描述"}],
+        "messages": [
+            {"index": 1, "type": "MissionId", "placeholder": ""},
+            {"index": 2, "type": "ChangeType", "placeholder": ""},
+            {"index": 3, "type": "Scopes", "placeholder": ""},
+            {"index": 4, "type": "Subject", "placeholder": ""}
+        ],
+        "changelog": {"order": ["fix"], "hidden": ["chore"], "missionUrl": "https://jira/browse/{id}"}
+    }))
+    .unwrap();
+    let messages: Vec<(String, String)> = [
+        ("1111111111", "PROJ-2 feat accounts add login"),
+        ("2222222222", "fix handle empty input"),
+        ("3333333333", "chore bump deps"),
+        ("4444444444", "not from rcz"),
+    ]
+    .iter()
+    .map(|(hash, message)| (hash.to_string(), message.to_string()))
+    .collect();
+
+    let entries = changelog::entries(&config, &messages);
+    assert_eq!(entries.len(), 3);
+    assert_eq!(
+        changelog::render(&config, "v1.0.0", "2022-06-01", &entries),
+        "## v1.0.0 (2022-06-01)\n\
+         \n### fix: A bug fix\n\
+         \n- handle empty input (22222222)\n\
+         \n### feat: A new feature\n\
+         \n#### accounts\n\
+         \n- add login ([PROJ-2](https://jira/browse/PROJ-2)) (11111111)\n"
+    );
+}
@@ -0,0 +1,129 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+const CONFIG: &str = r#"{
+  "ChangeType": [{"value": "feat", "name": "feat"}, {"value": "fix", "name": "fix"}],
+  "messages": [
+    {"index": 1, "type": "ChangeType", "alias": "-t", "placeholder": "type"},
+    {"index": 2, "type": "Subject", "alias": "-m", "placeholder": "subject"}
+  ]
+}"#;
+
+/// A fresh git repository with the test config
+fn repo(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rcz-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    git(&dir, &["init", "-q"]);
+    git(&dir, &["config", "user.name", "rcz"]);
+    git(&dir, &["config", "user.email", "rcz@example.com"]);
+    fs::write(dir.join("custom.json"), CONFIG).unwrap();
+    dir
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git").args(args).current_dir(dir).output().unwrap();
+    assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn rcz(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_input")).args(args).current_dir(dir).env("LANG", "en").output().unwrap()
+}
+
+#[test]
+fn amend_keeps_body_and_trailers() {
+    let dir = repo("amend");
+    fs::write(dir.join("a.txt"), "a").unwrap();
+    git(&dir, &["add", "a.txt"]);
+    git(&dir, &["commit", "-q", "-m", "fix old subject\n\nWhy it changed.\n\nRefs: #12"]);
+
+    let output = rcz(&dir, &["--amend", "-t", "feat", "-m", "new subject"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let message = git(&dir, &["log", "-1", "--format=%B"]);
+    assert_eq!(message.trim(), "feat new subject\n\nWhy it changed.\n\nRefs: #12");
+}
+
+#[test]
+fn hook_without_config_leaves_the_message() {
+    let dir = repo("hook");
+    fs::remove_file(dir.join("custom.json")).unwrap();
+    fs::write(dir.join("MSG"), "drafted in the IDE\n").unwrap();
+
+    let output = rcz(&dir, &["hook", "run", "MSG"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("leaving the commit message as is"));
+    assert_eq!(fs::read_to_string(dir.join("MSG")).unwrap(), "drafted in the IDE\n");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn hook_script_skips_without_a_terminal() {
+    let dir = repo("hook-script");
+    let script = dir.join("prepare-commit-msg");
+    fs::write(&script, git_message::hook::script(Path::new("/bin/false"))).unwrap();
+    // setsid 脱离控制终端, 和 IDE 或 CI 中一样
+    let status = Command::new("setsid").args(["-w", "sh"]).arg(&script).arg("MSG").current_dir(&dir).status().unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn bump_commit_uses_commit_options() {
+    let dir = repo("bump");
+    let config = CONFIG.replacen('{', r#"{"signoff": true, "bump": {"files": ["Cargo.toml"], "commit": {"ChangeType": "feat", "Subject": "release {version}"}},"#, 1);
+    fs::write(dir.join("custom.json"), config).unwrap();
+    fs::write(dir.join("Cargo.toml"), "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n").unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "feat add login"]);
+
+    let output = rcz(&dir, &["bump"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let message = git(&dir, &["log", "-1", "--format=%B"]);
+    assert_eq!(message.trim(), "feat release 0.1.0\n\nSigned-off-by: rcz <rcz@example.com>");
+}
+
+#[test]
+fn bump_updates_cargo_lock() {
+    let dir = repo("bump-lock");
+    let config = CONFIG.replacen('{', r#"{"bump": {"files": ["Cargo.toml"], "commit": {"ChangeType": "feat", "Subject": "release {version}"}},"#, 1);
+    fs::write(dir.join("custom.json"), config).unwrap();
+    fs::write(dir.join("Cargo.toml"), "[package]\nname = \"demo\"\nversion = \"0.0.0\"\n").unwrap();
+    fs::write(dir.join("Cargo.lock"), "version = 4\n\n[[package]]\nname = \"demo\"\nversion = \"0.0.0\"\n").unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "feat add login"]);
+
+    let output = rcz(&dir, &["bump"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(git(&dir, &["show", "HEAD:Cargo.lock"]).contains("version = \"0.1.0\""));
+    assert_eq!(git(&dir, &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn format_needs_print_or_output() {
+    let dir = repo("format");
+    let output = rcz(&dir, &["--format", "json", "-t", "feat", "-m", "add login"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--format: only with --print or --output"));
+
+    let output = rcz(&dir, &["--print", "--format", "json", "-t", "feat", "-m", "add login"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn bump_changes_nothing_when_a_file_fails() {
+    let dir = repo("bump-fail");
+    let config = CONFIG.replacen('{', r#"{"bump": {"files": ["Cargo.toml", "package.json"]},"#, 1);
+    fs::write(dir.join("custom.json"), config).unwrap();
+    fs::write(dir.join("Cargo.toml"), "[package]\nname = \"demo\"\nversion = \"0.0.0\"\n").unwrap();
+    fs::write(dir.join("package.json"), "{}\n").unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "feat add login"]);
+
+    let output = rcz(&dir, &["bump"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("package.json: no version to update"));
+    assert_eq!(git(&dir, &["status", "--porcelain"]), "");
+}
@@ -0,0 +1,51 @@
+use git_message::conventional::{ConventionalCommit, FooterSeparator, ParseErrorKind};
+
+#[test]
+fn parse_full_message() {
+    let message = "PROJ-1 feat(accounts,admin)!: drop legacy login\n\nThe old endpoint is gone.\n\nBREAKING CHANGE: /login was removed\nRefs #12";
+    let commit: ConventionalCommit = message.parse().unwrap();
+    assert_eq!(commit.mission_id.as_deref(), Some("PROJ-1"));
+    assert_eq!(commit.kind, "feat");
+    assert_eq!(commit.scopes, vec!["accounts", "admin"]);
+    assert!(commit.breaking);
+    assert_eq!(commit.subject, "drop legacy login");
+    assert_eq!(commit.body.as_deref(), Some("The old endpoint is gone."));
+    assert_eq!(commit.footers.len(), 2);
+    assert_eq!(commit.footers[1].separator, FooterSeparator::Hash);
+    assert_eq!(commit.footer_values("Refs").collect::<Vec<_>>(), vec!["12"]);
+    assert_eq!(commit.to_string(), message);
+}
+
+#[test]
+fn breaking_change_footer_without_marker() {
+    let commit: ConventionalCommit = "fix: handle null\n\nBREAKING CHANGE: returns Option".parse().unwrap();
+    assert!(!commit.breaking);
+    assert!(commit.is_breaking());
+    assert_eq!(commit.body, None);
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+    let cases = [
+        ("", ParseErrorKind::EmptyMessage, 0..0),
+        ("feat(accounts: x", ParseErrorKind::UnclosedScope, 4..16),
+        ("feat(a,): x", ParseErrorKind::EmptyScope, 7..7),
+        ("feat add login", ParseErrorKind::MissingSeparator, 4..6),
+        ("fe/at: x", ParseErrorKind::InvalidType, 0..5),
+        ("feat(a) x", ParseErrorKind::MissingSeparator, 7..9),
+        ("feat: ", ParseErrorKind::EmptySubject, 6..6),
+        ("feat: x\nbody", ParseErrorKind::MissingBlankLine, 8..12),
+    ];
+    for (message, kind, span) in cases {
+        let err = message.parse::<ConventionalCommit>().unwrap_err();
+        assert_eq!((err.kind, err.span), (kind, span), "{:?}", message);
+    }
+}
+
+#[test]
+fn colon_in_subject_is_not_a_mission_id() {
+    for (message, kind, subject) in [("feat: docs: update", "feat", "docs: update"), ("revert: feat: add x", "revert", "feat: add x")] {
+        let commit: ConventionalCommit = message.parse().unwrap();
+        assert_eq!((commit.mission_id, commit.kind.as_str(), commit.subject.as_str()), (None, kind, subject));
+    }
+}
@@ -0,0 +1,29 @@
+use git_message::display;
+
+#[test]
+fn display_width_of_emoji() {
+    assert_eq!(display::width("abc"), 3);
+    assert_eq!(display::width("✨ feat"), 7);
+    assert_eq!(display::normalize("♻️ refactor"), "♻  refactor");
+    assert_eq!(display::width("♻️ refactor"), 11);
+    assert_eq!(display::width("中文"), 4);
+}
+
+#[test]
+fn ellipsize_cuts_at_grapheme_boundaries() {
+    assert_eq!(display::ellipsize("feat: A new feature", 30), "feat: A new feature");
+    assert_eq!(display::ellipsize("feat: A new feature", 8), "feat: A…");
+    // 宽字符放不下时整个省略
+    assert_eq!(display::ellipsize("中文描述", 6), "中文…");
+    assert_eq!(display::ellipsize("中文描述", 5), "中文…");
+    assert_eq!(display::ellipsize("✨ feat", 3), "✨…");
+    assert_eq!(display::width(&display::ellipsize("♻️ refactor", 4)), 4);
+}
+
+#[test]
+fn wrap_breaks_at_spaces_and_wide_text() {
+    assert_eq!(display::wrap("White-space, formatting, missing semi-colons", 20), vec!["White-space,", "formatting, missing", "semi-colons"]);
+    assert_eq!(display::wrap("中文描述很长", 5), vec!["中文", "描述", "很长"]);
+    assert_eq!(display::wrap("first\nsecond", 20), vec!["first", "second"]);
+    assert_eq!(display::wrap("abcdef", 4), vec!["abcd", "ef"]);
+}
@@ -0,0 +1,64 @@
+use git_message::{
+    config::Config,
+    gitmoji::{self, EmojiFormat},
+};
+use serde_json::json;
+
+fn config(format: &str) -> Config {
+    Config::from_value(&json!({
+        "gitmoji": {"format": format},
+        "ChangeType": [
+            {"value": "feat", "emoji": ":sparkles:", "name": "feat: A new feature"},
+            {"emoji": "♻️", "name": "Refactor code"},
+            {"value": "chore", "name": "chore: Maintenance"}
+        ],
+        "messages": [
+            {"index": 1, "type": "ChangeType", "placeholder": "type"},
+            {"index": 2, "type": "Subject", "placeholder": "subject"}
+        ]
+    }))
+    .unwrap()
+}
+
+fn answers(values: &[&str]) -> Vec<String> {
+    values.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn lookup_either_form() {
+    assert_eq!(gitmoji::lookup(":recycle:"), Some((":recycle:", "♻️")));
+    assert_eq!(gitmoji::lookup("♻"), Some((":recycle:", "♻️")));
+    assert_eq!(gitmoji::render("✨", EmojiFormat::Code), ":sparkles:");
+    assert_eq!(gitmoji::render(":sparkles:", EmojiFormat::Unicode), "✨");
+    assert_eq!(gitmoji::render(":custom:", EmojiFormat::Unicode), ":custom:");
+}
+
+#[test]
+fn emoji_choices_render_and_parse() {
+    let config = config("code");
+    let choices = &config.select_map["ChangeType"];
+    assert_eq!(choices[0], ("✨ feat: A new feature".to_string(), "feat".to_string()));
+    assert_eq!(choices[1], ("♻️ Refactor code".to_string(), ":recycle:".to_string()));
+
+    let template = config.template();
+    let message = template.render(&answers(&["feat", "add login"]));
+    assert_eq!(message, ":sparkles: feat add login");
+    assert_eq!(template.parse(&message, &config.select_map), answers(&["feat", "add login"]));
+    assert_eq!(template.parse("✨ feat add login", &config.select_map), answers(&["feat", "add login"]));
+    assert_eq!(template.render(&answers(&[":recycle:", "tidy"])), ":recycle: tidy");
+    assert_eq!(template.render(&answers(&["chore", "bump"])), "chore bump");
+
+    let config = self::config("unicode");
+    assert_eq!(config.template().render(&answers(&["feat", "add login"])), "✨ feat add login");
+}
+
+#[test]
+fn bundled_list() {
+    let config = Config::from_value(&json!({
+        "ChangeType": {"source": "gitmoji"},
+        "messages": [{"index": 1, "type": "ChangeType", "placeholder": "type"}]
+    }))
+    .unwrap();
+    let choices = &config.select_map["ChangeType"];
+    assert!(choices.contains(&("✨ :sparkles: Introduce new features".to_string(), ":sparkles:".to_string())));
+}
@@ -0,0 +1,30 @@
+use git_message::history::{History, SelectDefault};
+use serde_json::json;
+
+#[test]
+fn recent_frequent_and_completion() {
+    let history = History::from_value(&json!({
+        "MissionId": [
+            {"value": "PROJ-12", "count": 1, "lastUsed": 300},
+            {"value": "PROJ-7", "count": 5, "lastUsed": 200},
+            {"value": "OPS-1", "count": 2, "lastUsed": 100}
+        ]
+    }));
+    assert_eq!(history.recent("MissionId"), vec!["PROJ-12", "PROJ-7", "OPS-1"]);
+    assert_eq!(history.default_choice("MissionId", SelectDefault::Recent), Some("PROJ-12"));
+    assert_eq!(history.default_choice("MissionId", SelectDefault::Frequent), Some("PROJ-7"));
+    assert_eq!(history.complete("MissionId", "PROJ-"), Some("PROJ-12"));
+    assert_eq!(history.complete("MissionId", "OPS-1"), None);
+    assert!(history.recent("Subject").is_empty());
+}
+
+#[test]
+fn record_moves_value_to_front() {
+    let mut history = History::default();
+    history.record("ChangeType", "fix");
+    history.record("ChangeType", "feat");
+    history.record("ChangeType", "fix");
+    history.record("ChangeType", " ");
+    assert_eq!(history.recent("ChangeType"), vec!["fix", "feat"]);
+    assert_eq!(history.most_frequent("ChangeType"), Some("fix"));
+}
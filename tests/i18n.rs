@@ -0,0 +1,69 @@
+use git_message::{
+    config::Config,
+    i18n::{self, Locale},
+    CommitMessage,
+};
+use serde_json::json;
+
+#[test]
+fn locale_tags() {
+    assert_eq!(Locale::from_tag("zh_CN.UTF-8"), Some(Locale::ZhCn));
+    assert_eq!(Locale::from_tag("zh-TW"), Some(Locale::ZhCn));
+    assert_eq!(Locale::from_tag("en_US.UTF-8"), Some(Locale::En));
+    assert_eq!(Locale::from_tag("C"), None);
+}
+
+#[test]
+fn per_locale_config_strings() {
+    let v = json!({
+        "ChangeType": [{"value": "feat", "name": {"zh-CN": "新功能", "en": "A new feature"}}],
+        "messages": [
+            {"index": 1, "type": "ChangeType", "placeholder": {"zh-CN": "请选择变更类型", "en": "Type of change"}},
+            {"index": 2, "type": "Subject", "placeholder": "Subject"}
+        ]
+    });
+    let mut zh = v.clone();
+    zh["locale"] = json!("zh-CN");
+    let config = Config::from_value(&zh).unwrap();
+    assert_eq!(config.locale, Locale::ZhCn);
+    assert_eq!(config.command_map[0].0, "请选择变更类型");
+    assert_eq!(config.select_map["ChangeType"][0].0, "新功能");
+    assert_eq!(config.command_map[1].0, "Subject");
+
+    let mut en = v;
+    en["locale"] = json!("en");
+    let config = Config::from_value(&en).unwrap();
+    assert_eq!(config.command_map[0].0, "Type of change");
+    assert_eq!(config.select_map["ChangeType"][0].0, "A new feature");
+
+    // 没有对应语言时用英文
+    assert_eq!(Locale::ZhCn.pick(&json!({"en": "x", "fr": "y"})), Some("x".to_string()));
+}
+
+#[test]
+fn catalogs() {
+    let mut message = CommitMessage::with_locale(Locale::En);
+    assert_eq!(message.status().content(), "Mission ID:");
+    message.next();
+    assert_eq!(message.status().content(), Locale::En.catalog().change_type_prompt);
+    assert_eq!(CommitMessage::with_locale(Locale::ZhCn).status().content(), "请输入任务ID:");
+
+    let title = i18n::fill(Locale::ZhCn.catalog().log_title, &[("scope", "全部"), ("key", "F2")]);
+    assert_eq!(title, "git log (全部, F2 切换)");
+}
+
+#[test]
+fn choice_descriptions_per_locale() {
+    let config = Config::from_value(&json!({
+        "locale": "zh-CN",
+        "ChangeType": [
+            {"value": "style", "name": "style", "description": {"zh-CN": "格式调整", "en": "Formatting"}},
+            {"value": "feat", "name": "feat", "description": " "}
+        ],
+        "messages": [{"index": 1, "type": "ChangeType", "placeholder": "type"}]
+    }))
+    .unwrap();
+    assert_eq!(config.descriptions["ChangeType"]["style"], "格式调整");
+    assert!(!config.descriptions["ChangeType"].contains_key("feat"));
+    assert_eq!(config.select_map["ChangeType"][0], ("style".to_string(), "style".to_string()));
+}
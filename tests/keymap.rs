@@ -0,0 +1,79 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde_json::json;
+use git_message::{
+    i18n::Locale,
+    keymap::{Action, KeyChord, Keymap, Mode},
+};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+#[test]
+fn default_bindings_drive_actions() {
+    let keymap = Keymap::default();
+    assert_eq!(keymap.action(Mode::Select, &key(KeyCode::Left)), Some(Action::Back));
+    assert_eq!(keymap.action(Mode::Select, &KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT)), Some(Action::Help));
+    // 输入框中 ? 是普通字符
+    assert_eq!(keymap.action(Mode::Type, &key(KeyCode::Char('?'))), None);
+    assert_eq!(keymap.action(Mode::Type, &key(KeyCode::F(1))), Some(Action::Help));
+    assert_eq!(keymap.action(Mode::Restore, &key(KeyCode::Char('n'))), Some(Action::Discard));
+    assert_eq!(keymap.action(Mode::Confirm, &key(KeyCode::Left)), None);
+    assert_eq!(keymap.action(Mode::Confirm, &key(KeyCode::F(4))), Some(Action::TogglePreview));
+}
+
+#[test]
+fn help_lists_every_binding_of_the_mode() {
+    let keymap = Keymap::default();
+    let bindings = keymap.bindings(Mode::Restore);
+    let submit = bindings.iter().find(|(action, _)| *action == Action::Submit).unwrap();
+    assert_eq!(submit.1, vec![KeyChord::new(KeyCode::Char('y')), KeyChord::new(KeyCode::Enter)]);
+    assert!(bindings.iter().any(|(action, _)| *action == Action::Abort));
+
+    let catalog = Locale::En.catalog();
+    assert_eq!(Action::Submit.description(Mode::Restore, catalog), "restore draft");
+    assert_eq!(Action::Previous.description(Mode::Type, catalog), "older history entry");
+    assert_eq!(KeyChord::ctrl('n').to_string(), "Ctrl+n");
+}
+
+#[test]
+fn parses_key_chords() {
+    assert_eq!(KeyChord::parse("ctrl+n"), Some(KeyChord::ctrl('n')));
+    assert_eq!(KeyChord::parse("C-p"), Some(KeyChord::ctrl('p')));
+    assert_eq!(KeyChord::parse("M-x"), Some(KeyChord { code: KeyCode::Char('x'), modifiers: KeyModifiers::ALT }));
+    assert_eq!(KeyChord::parse("PageDown"), Some(KeyChord::new(KeyCode::PageDown)));
+    assert_eq!(KeyChord::parse("f12"), Some(KeyChord::new(KeyCode::F(12))));
+    assert_eq!(KeyChord::parse("/"), Some(KeyChord::new(KeyCode::Char('/'))));
+    assert_eq!(KeyChord::parse("f13"), None);
+    assert_eq!(KeyChord::parse("hyper"), None);
+}
+
+#[test]
+fn presets_add_their_keys() {
+    let vim = Keymap::preset("vim").unwrap();
+    assert_eq!(vim.action(Mode::Select, &key(KeyCode::Char('j'))), Some(Action::Next));
+    assert_eq!(vim.action(Mode::Select, &KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL)), Some(Action::Previous));
+    // 输入框中 j/k 仍是普通字符, Ctrl 组合键可用
+    assert_eq!(vim.action(Mode::Type, &key(KeyCode::Char('j'))), None);
+    assert_eq!(vim.action(Mode::Type, &KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL)), Some(Action::Next));
+    assert_eq!(vim.action(Mode::Select, &key(KeyCode::Down)), Some(Action::Next));
+
+    let emacs = Keymap::preset("emacs").unwrap();
+    assert_eq!(emacs.action(Mode::Confirm, &KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL)), Some(Action::Abort));
+    assert_eq!(emacs.action(Mode::Select, &KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL)), Some(Action::Filter));
+    assert!(Keymap::preset("nano").is_none());
+}
+
+#[test]
+fn config_overrides_replace_preset_keys() {
+    let keymap = Keymap::from_value(&json!({"preset": "vim", "bindings": {"toggle": "f3", "scroll-preview": ["ctrl+e", "pagedown"]}})).unwrap();
+    assert_eq!(keymap.action(Mode::Type, &key(KeyCode::F(3))), Some(Action::ToggleLog));
+    assert_eq!(keymap.action(Mode::Type, &key(KeyCode::F(2))), None);
+    assert_eq!(keymap.keys(Mode::Select, Action::ScrollPreview), vec![KeyChord::ctrl('e'), KeyChord::new(KeyCode::PageDown)]);
+    assert_eq!(keymap.action(Mode::Select, &key(KeyCode::Char('k'))), Some(Action::Previous));
+
+    assert!(Keymap::from_value(&json!("emacs")).is_ok());
+    assert!(Keymap::from_value(&json!({"bindings": {"jump": "g"}})).unwrap_err().contains("keys.bindings.jump"));
+    assert!(Keymap::from_value(&json!({"bindings": {"next": "hyper"}})).unwrap_err().contains("hyper"));
+    assert!(Keymap::from_value(&json!({"preset": "nano"})).is_err());
+}
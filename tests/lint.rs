@@ -0,0 +1,34 @@
+use git_message::{config::Config, lint::Linter};
+use serde_json::json;
+
+fn config() -> Config {
+    Config::from_value(&json!({
+        "ChangeType": [{"value": "feat", "name": "feat: A new feature"}],
+        "Scopes": [{"value": "accounts", "name": "描述"}],
+        "messages": [
+            {"index": 1, "type": "MissionId", "placeholder": ""},
+            {"index": 2, "type": "ChangeType", "placeholder": ""},
+            {"index": 3, "type": "Scopes", "placeholder": ""},
+            {"index": 4, "type": "Subject", "placeholder": ""}
+        ],
+        "rules": {"headerMaxLength": 30, "missionIdPattern": "^[A-Z]+-\\d+$", "required": ["ChangeType", "Subject"]}
+    }))
+    .unwrap()
+}
+
+#[test]
+fn valid_message_passes() {
+    let config = config();
+    let linter = Linter::new(&config).unwrap();
+    assert!(linter.lint("PROJ-1 feat accounts add login").is_empty());
+    assert!(linter.lint("feat add login").is_empty());
+}
+
+#[test]
+fn reports_every_broken_rule() {
+    let config = config();
+    let linter = Linter::new(&config).unwrap();
+    let violations = linter.lint("proj1 build accounts add a login page to the app");
+    let steps: Vec<Option<&str>> = violations.iter().map(|v| v.step.as_deref()).collect();
+    assert_eq!(steps, vec![None, Some("MissionId"), Some("ChangeType")]);
+}
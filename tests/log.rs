@@ -0,0 +1,21 @@
+use git_message::log::{highlight, parse_log};
+
+#[test]
+fn parse_multi_line_and_merge_commits() {
+    let output = "a1b2c3d\x1fAlice\x1f2 hours ago\x1fPROJ-1 feat accounts add login\x1e\n\
+                  e4f5a6b\x1fBob\x1f3 days ago\x1fMerge branch 'dev'\x1e\n";
+    let entries = parse_log(output);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].hash, "a1b2c3d");
+    assert_eq!(entries[0].author, "Alice");
+    assert_eq!(entries[0].date, "2 hours ago");
+    assert_eq!(entries[1].header, "Merge branch 'dev'");
+}
+
+#[test]
+fn highlight_known_values() {
+    let tokens = highlight("feat(accounts): add login", &["feat", "accounts"]);
+    let flagged: Vec<&str> = tokens.iter().filter(|(_, known)| *known).map(|(t, _)| *t).collect();
+    assert_eq!(flagged, vec!["feat", "accounts"]);
+    assert_eq!(tokens.iter().map(|(t, _)| *t).collect::<String>(), "feat(accounts): add login");
+}
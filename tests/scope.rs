@@ -0,0 +1,48 @@
+use git_message::{
+    glob,
+    scope::{self, ScopeRule, Suggestion},
+};
+
+fn rule(pattern: &str, scope: &str) -> ScopeRule {
+    ScopeRule { pattern: pattern.to_string(), scope: scope.to_string() }
+}
+
+#[test]
+fn glob_matches() {
+    assert!(glob::matches("src/accounts/**", "src/accounts/login.rs"));
+    assert!(glob::matches("src/accounts/**", "src/accounts/api/mod.rs"));
+    assert!(glob::matches("**/*.md", "README.md"));
+    assert!(glob::matches("**/*.md", "docs/guide/intro.md"));
+    assert!(glob::matches("packages/*", "packages/core"));
+    assert!(glob::matches("src/?.rs", "src/a.rs"));
+    assert!(!glob::matches("packages/*", "packages/core/src"));
+    assert!(!glob::matches("src/*.rs", "src/accounts/login.rs"));
+    assert!(!glob::matches("src/?.rs", "src/ab.rs"));
+}
+
+#[test]
+fn suggest_orders_by_matching_files() {
+    let rules = vec![rule("docs/**", "docs"), rule("src/accounts/**", "accounts"), rule("src/billing/**", "billing")];
+    let paths = vec!["src/accounts/a.rs".to_string(), "src/accounts/b.rs".to_string(), "docs/x.md".to_string()];
+    let suggestions = scope::suggest(&rules, &paths);
+    assert_eq!(
+        suggestions,
+        vec![
+            Suggestion { scope: "accounts".to_string(), files: vec!["src/accounts/a.rs".to_string(), "src/accounts/b.rs".to_string()] },
+            Suggestion { scope: "docs".to_string(), files: vec!["docs/x.md".to_string()] },
+        ]
+    );
+}
+
+#[test]
+fn reorder_moves_suggestions_to_top() {
+    let mut choices: Vec<(String, String)> =
+        ["billing", "docs", "accounts"].iter().map(|v| (v.to_string(), v.to_string())).collect();
+    let suggestions = scope::suggest(&[rule("src/**", "accounts")], &["src/a.rs".to_string()]);
+    scope::reorder(&mut choices, &suggestions);
+    let values: Vec<&str> = choices.iter().map(|(_, v)| v.as_str()).collect();
+    assert_eq!(values, vec!["accounts", "billing", "docs"]);
+
+    scope::reorder(&mut choices, &[]);
+    assert_eq!(choices.len(), 3);
+}
@@ -0,0 +1,71 @@
+use std::{fs, time::Duration};
+
+use git_message::{
+    config::Config,
+    source::{self, CommandSource},
+};
+use serde_json::json;
+
+fn sh(script: &str, timeout: u64) -> CommandSource {
+    CommandSource {
+        command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+        timeout: Duration::from_secs(timeout),
+        cache: Duration::ZERO,
+    }
+}
+
+fn pair(name: &str, value: &str) -> (String, String) {
+    (name.to_string(), value.to_string())
+}
+
+#[test]
+fn parse_json_and_lines() {
+    let json = r#"[{"name": "PROJ-1: login", "value": "PROJ-1"}, {"value": "PROJ-2"}]"#;
+    assert_eq!(source::parse_output(json).unwrap(), vec![pair("PROJ-1: login", "PROJ-1"), pair("PROJ-2", "PROJ-2")]);
+    assert_eq!(
+        source::parse_output("PROJ-1\tlogin\n\nPROJ-2\n").unwrap(),
+        vec![pair("login", "PROJ-1"), pair("PROJ-2", "PROJ-2")]
+    );
+    assert!(source::parse_output("[{\"name\": 1}]").is_err());
+}
+
+#[test]
+fn run_command() {
+    let source = sh("printf 'r1\\tRelease 1\\nr2\\tRelease 2\\n'", 5);
+    assert_eq!(source.choices(), vec![pair("Release 1", "r1"), pair("Release 2", "r2")]);
+}
+
+#[test]
+fn failure_becomes_error_choice() {
+    let choices = sh("exit 3", 5).choices();
+    assert_eq!(choices.len(), 1);
+    assert!(choices[0].0.starts_with("! sh -c exit 3: exited with"));
+    assert_eq!(choices[0].1, "");
+    assert!(CommandSource::is_error(&choices[0]));
+    assert!(!CommandSource::is_error(&pair("none", "")));
+
+    assert_eq!(sh("sleep 5", 1).run(), Err("timed out after 1s".to_string()));
+}
+
+#[test]
+fn sources_run_only_when_loaded() {
+    let marker = std::env::temp_dir().join(format!("rcz-source-{}", std::process::id()));
+    let _ = fs::remove_file(&marker);
+    let script = format!("touch {}; echo r1", marker.display());
+    let mut config = Config::from_value(&json!({
+        "Release": {"source": {"command": ["sh", "-c", script], "cache": 0}},
+        "messages": [{"index": 1, "type": "Release", "placeholder": "release"}]
+    }))
+    .unwrap();
+    assert!(!marker.exists());
+    assert!(!config.select_map.contains_key("Release"));
+    // 已给出答案的步骤不运行命令
+    config.load_sources(&["Subject".to_string()]);
+    assert!(!marker.exists());
+
+    config.load_sources(&["Release".to_string()]);
+    assert!(marker.exists());
+    assert_eq!(config.select_map["Release"], vec![pair("r1", "r1")]);
+    assert!(config.command_sources.is_empty());
+    let _ = fs::remove_file(&marker);
+}
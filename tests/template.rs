@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use git_message::template::Template;
+
+fn select_map() -> HashMap<String, Vec<(String, String)>> {
+    let mut map = HashMap::new();
+    map.insert("ChangeType".to_string(), vec![("feat: A new feature".to_string(), "feat".to_string())]);
+    map.insert("Scopes".to_string(), vec![("描述".to_string(), "accounts".to_string())]);
+    map
+}
+
+fn template() -> Template {
+    Template::new(["MissionId", "ChangeType", "Scopes", "Subject", "Body", "Footers"].iter().map(|s| s.to_string()).collect())
+}
+
+#[test]
+fn render_and_parse_round_trip() {
+    let answers: Vec<String> = ["PROJ-1", "feat", "accounts", "add login page", "Longer explanation.", "Refs: #12"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let message = template().render(&answers);
+    assert_eq!(message, "PROJ-1 feat accounts add login page\n\nLonger explanation.\n\nRefs: #12");
+    assert_eq!(template().parse(&message, &select_map()), answers);
+}
+
+#[test]
+fn parse_skips_empty_optional_steps() {
+    let answers = template().parse("feat add login page", &select_map());
+    assert_eq!(answers, vec!["", "feat", "", "add login page", "", ""]);
+}
+
+#[test]
+fn unparsed_keeps_what_no_step_covers() {
+    let message = "feat add login page\n\nLonger explanation.\n\nRefs: #12";
+    let header_only = Template::new(["ChangeType", "Subject"].iter().map(|s| s.to_string()).collect());
+    assert_eq!(header_only.unparsed(message), "Longer explanation.\n\nRefs: #12");
+    let with_footers = Template::new(["ChangeType", "Subject", "Footers"].iter().map(|s| s.to_string()).collect());
+    assert_eq!(with_footers.unparsed(message), "Longer explanation.");
+    assert_eq!(template().unparsed(message), "");
+}
@@ -1,8 +1,7 @@
 use std::fs;
-use std::io;
 
 #[test]
-fn readFile() {
-    let mut data = fs::read_to_string("custom.json");
+fn read_file() {
+    let data = fs::read_to_string("custom.json");
     println!("{:?}", data);
 }
@@ -0,0 +1,41 @@
+use git_message::theme::{self, Theme};
+use serde_json::json;
+use tui::style::{Color, Modifier};
+
+#[test]
+fn presets_and_overrides() {
+    assert_eq!(Theme::from_value(&json!("light")).unwrap(), Theme::light());
+    assert_eq!(Theme::from_value(&json!({})).unwrap(), Theme::dark());
+
+    let theme = Theme::from_value(&json!({
+        "preset": "high-contrast",
+        "styles": {"highlight": {"bg": "#ffd700", "bold": false}, "hash": {"fg": 208}}
+    }))
+    .unwrap();
+    assert_eq!(theme.highlight.bg, Some(Color::Rgb(255, 215, 0)));
+    assert_eq!(theme.highlight.fg, Some(Color::Black));
+    assert!(!theme.highlight.add_modifier.contains(Modifier::BOLD));
+    assert_eq!(theme.hash.fg, Some(Color::Indexed(208)));
+
+    assert!(Theme::from_value(&json!("solarized")).is_err());
+    assert!(Theme::from_value(&json!({"styles": {"border": {}}})).is_err());
+    assert!(Theme::from_value(&json!({"styles": {"item": {"fg": "chartreuse"}}})).is_err());
+}
+
+#[test]
+fn colour_names() {
+    assert_eq!(theme::color(&json!("light-green")), Ok(Color::LightGreen));
+    assert_eq!(theme::color(&json!("DarkGrey")), Ok(Color::DarkGray));
+    assert!(theme::color(&json!("#12345")).is_err());
+    assert!(theme::color(&json!(256)).is_err());
+}
+
+#[test]
+fn no_color_keeps_highlight_visible() {
+    let theme = Theme::dark().without_color();
+    assert_eq!(theme.item.fg, None);
+    assert_eq!(theme.item.bg, None);
+    assert_eq!(theme.hash.fg, None);
+    assert!(theme.highlight.add_modifier.contains(Modifier::REVERSED));
+    assert!(theme.known.add_modifier.contains(Modifier::BOLD));
+}
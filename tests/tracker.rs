@@ -0,0 +1,81 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use git_message::tracker::{self, Ticket, TrackerConfig};
+use serde_json::json;
+
+/// Serve one request per response with a local mock tracker, sending each request head back
+fn mock_server(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/issue/{{id}}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            tx.send(head).unwrap();
+            let response = format!("HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body);
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (url, rx)
+}
+
+fn config(url: &str) -> TrackerConfig {
+    TrackerConfig {
+        url: url.to_string(),
+        token_env: Some("RCZ_TEST_TRACKER_TOKEN".to_string()),
+        auth_header: "Authorization: Bearer {token}".to_string(),
+        title_field: "fields.summary".to_string(),
+        timeout: Duration::from_secs(5),
+    }
+}
+
+#[test]
+fn lookup_against_mock_server() {
+    std::env::set_var("RCZ_TEST_TRACKER_TOKEN", "secret");
+    let (url, requests) = mock_server(vec![(200, r#"{"fields": {"summary": "Fix login"}}"#), (404, "{}"), (500, "")]);
+    let config = config(&url);
+
+    assert_eq!(tracker::lookup(&config, "PROJ-1"), Ticket::Found { id: "PROJ-1".to_string(), title: "Fix login".to_string() });
+    let head = requests.recv().unwrap();
+    assert!(head.starts_with("GET /issue/PROJ-1 "));
+    assert!(head.contains("Authorization: Bearer secret"));
+
+    assert_eq!(tracker::lookup(&config, "PROJ-404"), Ticket::NotFound { id: "PROJ-404".to_string() });
+    assert_eq!(
+        tracker::lookup(&config, "PROJ-2"),
+        Ticket::Unavailable { id: "PROJ-2".to_string(), reason: "HTTP 500".to_string() }
+    );
+}
+
+#[test]
+fn offline_is_unavailable() {
+    // 绑定后立即释放端口, 连接会被拒绝
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let config = config(&format!("http://127.0.0.1:{}/issue/{{id}}", port));
+    assert!(matches!(tracker::lookup(&config, "PROJ-1"), Ticket::Unavailable { .. }));
+}
+
+#[test]
+fn title_paths() {
+    let v = json!({"fields": {"summary": "a"}, "title": "b", "labels": [{"name": "c"}]});
+    assert_eq!(tracker::title_at(&v, "fields.summary"), Some("a".to_string()));
+    assert_eq!(tracker::title_at(&v, "title"), Some("b".to_string()));
+    assert_eq!(tracker::title_at(&v, "labels.0.name"), Some("c".to_string()));
+    assert_eq!(tracker::title_at(&v, "fields.missing"), None);
+}
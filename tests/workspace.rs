@@ -0,0 +1,63 @@
+use std::{fs, path::PathBuf};
+
+use git_message::workspace::{self, ScopeSource};
+use serde_json::json;
+
+/// Fresh directory under the system temp dir
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rcz-workspace-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn cargo_members_across_lines() {
+    let content = "[package]\nname = \"root\"\n\n[workspace]\nmembers = [\n    \"crates/*\", # libraries\n    \"cli\",\n]\n\n[dependencies]\nmembers = 1\n";
+    assert_eq!(workspace::cargo_members(content), vec!["crates/*", "cli"]);
+    assert_eq!(workspace::cargo_package_name(content), Some("root".to_string()));
+}
+
+#[test]
+fn npm_workspaces_array_or_object() {
+    assert_eq!(workspace::npm_workspaces(&json!({"workspaces": ["packages/*"]})), vec!["packages/*"]);
+    assert_eq!(workspace::npm_workspaces(&json!({"workspaces": {"packages": ["apps/*"]}})), vec!["apps/*"]);
+    assert!(workspace::npm_workspaces(&json!({})).is_empty());
+}
+
+#[test]
+fn discover_cargo_workspace() {
+    let root = temp_dir("cargo");
+    fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+    fs::create_dir_all(root.join("crates/core")).unwrap();
+    fs::write(root.join("crates/core/Cargo.toml"), "[package]\nname = \"acme-core\"\n").unwrap();
+    fs::create_dir_all(root.join("crates/util")).unwrap();
+
+    let choices = workspace::discover(&ScopeSource::CargoWorkspace, &root).unwrap();
+    assert_eq!(
+        choices,
+        vec![
+            ("acme-core (crates/core)".to_string(), "acme-core".to_string()),
+            ("util (crates/util)".to_string(), "util".to_string()),
+        ]
+    );
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn discover_npm_workspaces_and_directories() {
+    let root = temp_dir("npm");
+    fs::write(root.join("package.json"), r#"{"workspaces": ["packages/*"]}"#).unwrap();
+    fs::create_dir_all(root.join("packages/web/node_modules/dep")).unwrap();
+    fs::write(root.join("packages/web/package.json"), r#"{"name": "@acme/web"}"#).unwrap();
+
+    let choices = workspace::discover(&ScopeSource::NpmWorkspaces, &root).unwrap();
+    assert_eq!(choices, vec![("web (packages/web)".to_string(), "web".to_string())]);
+
+    let source = ScopeSource::from_name("directories", Some("packages/w*")).unwrap();
+    let values: Vec<String> = workspace::discover(&source, &root).unwrap().into_iter().map(|(_, v)| v).collect();
+    assert_eq!(values, vec!["web"]);
+
+    assert!(workspace::discover(&ScopeSource::CargoWorkspace, &root).is_err());
+    fs::remove_dir_all(root).unwrap();
+}